@@ -1,31 +1,69 @@
 import { Connection, PublicKey } from "@solana/web3.js";
+import BN from "bn.js";
+import bs58 from "bs58";
 import { TOKEN_VESTING_ID } from "./bindings";
+import { OwnerRegistry, Tag } from "./state";
 
 /**
- * This function can be used to retrieve the EXAMPLE accounts of an owner
+ * Offset of the owner key in the data of a vesting contract account
+ */
+const OWNER_OFFSET = 8;
+
+/**
+ * This function can be used to retrieve the vesting contracts of an owner by scanning the
+ * program accounts. Some RPC providers disable or rate-limit this call, see
+ * `getRegistryForOwner` for the contracts registered with their owner.
  * @param connection A solana RPC connection
  * @param owner The owner
+ * @param programId The program ID
  * @returns
  */
-export const getForOwner = async (connection: Connection, owner: PublicKey) => {
+export const getForOwner = async (
+  connection: Connection,
+  owner: PublicKey,
+  programId = TOKEN_VESTING_ID
+) => {
   const filters = [
     {
       memcmp: {
         offset: 0,
-        bytes: "3", // Account Tag EXAMPLE
+        bytes: bs58.encode(
+          new BN(Tag.VestingContract).toArrayLike(Buffer, "le", 8)
+        ),
       },
     },
     {
       memcmp: {
-        offset: 1,
+        offset: OWNER_OFFSET,
         bytes: owner.toBase58(),
       },
     },
   ];
 
-  const result = await connection.getProgramAccounts(TOKEN_VESTING_ID, {
+  const result = await connection.getProgramAccounts(programId, {
     filters,
   });
 
   return result;
 };
+
+/**
+ * This function can be used to retrieve the keys of the vesting contracts listed in the
+ * registry of an owner, with a single account lookup
+ * @param connection A solana RPC connection
+ * @param owner The owner
+ * @param programId The program ID
+ * @returns The registered contracts, empty if the owner has no registry
+ */
+export const getRegistryForOwner = async (
+  connection: Connection,
+  owner: PublicKey,
+  programId = TOKEN_VESTING_ID
+) => {
+  const [key] = await OwnerRegistry.findKey(programId, owner);
+  const accountInfo = await connection.getAccountInfo(key);
+  if (!accountInfo || !accountInfo.data) {
+    return [];
+  }
+  return OwnerRegistry.deserialize(accountInfo.data).contracts;
+};
@@ -4,7 +4,10 @@ import BN from "bn.js";
 
 export enum Tag {
   Uninitialized = 0,
-  Initialized = 1,
+  VestingContract = 1,
+  ExampleStateBorsh = 2,
+  MintStats = 3,
+  OwnerRegistry = 4,
 }
 
 export class ExampleState {
@@ -48,3 +51,131 @@ export class ExampleState {
     );
   }
 }
+
+/**
+ * Aggregate counters of the vesting contracts of a mint, held by a PDA of the mint.
+ * Contracts migrated from the legacy layout aren't counted.
+ */
+export class MintStats {
+  static SEED = "mint_stats";
+  tag: Tag;
+  mint: PublicKey;
+  totalLocked: BN;
+  totalClaimed: BN;
+  activeContracts: BN;
+  totalContracts: BN;
+
+  static schema: Schema = new Map([
+    [
+      MintStats,
+      {
+        kind: "struct",
+        fields: [
+          ["tag", "u64"],
+          ["mint", [32]],
+          ["totalLocked", "u64"],
+          ["totalClaimed", "u64"],
+          ["activeContracts", "u64"],
+          ["totalContracts", "u64"],
+        ],
+      },
+    ],
+  ]);
+
+  constructor(obj: {
+    tag: BN;
+    mint: Uint8Array;
+    totalLocked: BN;
+    totalClaimed: BN;
+    activeContracts: BN;
+    totalContracts: BN;
+  }) {
+    this.tag = obj.tag.toNumber() as Tag;
+    this.mint = new PublicKey(obj.mint);
+    this.totalLocked = obj.totalLocked;
+    this.totalClaimed = obj.totalClaimed;
+    this.activeContracts = obj.activeContracts;
+    this.totalContracts = obj.totalContracts;
+  }
+
+  static deserialize(data: Buffer): MintStats {
+    const stats = deserialize(this.schema, MintStats, data);
+    if (stats.tag !== Tag.MintStats) {
+      throw new Error("Not a mint statistics account");
+    }
+    return stats;
+  }
+
+  static async retrieve(connection: Connection, key: PublicKey) {
+    const accountInfo = await connection.getAccountInfo(key);
+    if (!accountInfo || !accountInfo.data) {
+      throw new Error("Mint statistics account not found");
+    }
+    return this.deserialize(accountInfo.data);
+  }
+
+  static async findKey(programId: PublicKey, mint: PublicKey) {
+    return await PublicKey.findProgramAddress(
+      [Buffer.from(MintStats.SEED), mint.toBuffer()],
+      programId
+    );
+  }
+
+  /** Fetch the statistics of a mint, which has none until its first contract is created */
+  static async retrieveForMint(
+    connection: Connection,
+    programId: PublicKey,
+    mint: PublicKey
+  ) {
+    const [key] = await this.findKey(programId, mint);
+    return await this.retrieve(connection, key);
+  }
+}
+
+/**
+ * The list of the vesting contracts of an owner, held by a PDA of the owner.
+ * Only the contracts created with the `RegisterOwner` flag are listed.
+ */
+export class OwnerRegistry {
+  static SEED = "owner_registry";
+  owner: PublicKey;
+  contracts: PublicKey[];
+
+  constructor(obj: { owner: PublicKey; contracts: PublicKey[] }) {
+    this.owner = obj.owner;
+    this.contracts = obj.contracts;
+  }
+
+  static deserialize(data: Buffer): OwnerRegistry {
+    if (
+      data.length < 40 ||
+      (data.length - 40) % 32 !== 0 ||
+      !new BN(data.slice(0, 8), "le").eqn(Tag.OwnerRegistry)
+    ) {
+      throw new Error("Not an owner registry account");
+    }
+    const contracts: PublicKey[] = [];
+    for (let offset = 40; offset < data.length; offset += 32) {
+      contracts.push(new PublicKey(data.slice(offset, offset + 32)));
+    }
+    return new OwnerRegistry({
+      owner: new PublicKey(data.slice(8, 40)),
+      contracts,
+    });
+  }
+
+  static async retrieve(connection: Connection, key: PublicKey) {
+    const accountInfo = await connection.getAccountInfo(key);
+    if (!accountInfo || !accountInfo.data) {
+      throw new Error("Owner registry account not found");
+    }
+    return this.deserialize(accountInfo.data);
+  }
+
+  static async findKey(programId: PublicKey, owner: PublicKey) {
+    return await PublicKey.findProgramAddress(
+      [Buffer.from(OwnerRegistry.SEED), owner.toBuffer()],
+      programId
+    );
+  }
+}
@@ -21,6 +21,29 @@ pub fn main() {
         compute_budget.push(res[res.len() - 1]);
     }
     bench_runner.commit(schedules_length, compute_budget);
+
+    // The cost of a single claim releasing every schedule up to a position of the longest
+    // schedule, with the linear layout and then the cumulative one
+    let claim_positions = (0..max_schedules)
+        .step_by((max_schedules / samples) as usize)
+        .collect::<Vec<_>>();
+
+    for is_cumulative in [0, 1] {
+        let bench_runner = BenchRunner::new(test_name!(), token_vesting::ID);
+        let mut compute_budget = Vec::with_capacity(claim_positions.len());
+        for claim_position in claim_positions.iter() {
+            let res = bench_runner.run(&[
+                max_schedules.to_string(),
+                claim_position.to_string(),
+                is_cumulative.to_string(),
+            ]);
+            if res.is_empty() {
+                panic!("{} {}", claim_position, is_cumulative)
+            }
+            compute_budget.push(res[res.len() - 1]);
+        }
+        bench_runner.commit(claim_positions.clone(), compute_budget);
+    }
 }
 
 #[cfg(not(feature = "benchmarking"))]
@@ -0,0 +1,437 @@
+//! Helpers to call the token vesting program from other programs
+//!
+//! Each helper builds the instruction from the keys of the given accounts and invokes it.
+//! The `signer_seeds` are forwarded to `invoke_signed` for the accounts which are PDAs of the
+//! calling program, and can be left empty otherwise.
+
+use solana_program::{
+    account_info::AccountInfo, entrypoint::ProgramResult, program::invoke_signed,
+    program_error::ProgramError, pubkey::Pubkey,
+};
+
+use crate::instruction;
+
+#[allow(missing_docs)]
+pub fn create<'a>(
+    token_vesting_program: &AccountInfo<'a>,
+    accounts: instruction::create::Accounts<AccountInfo<'a>>,
+    params: instruction::create::Params,
+    signer_seeds: &[&[&[u8]]],
+) -> ProgramResult {
+    let ix = instruction::create(
+        instruction::create::Accounts {
+            spl_token_program: accounts.spl_token_program.key,
+            vesting_contract: accounts.vesting_contract.key,
+            vault: accounts.vault.key,
+            source_tokens: accounts.source_tokens.key,
+            source_tokens_owner: accounts.source_tokens_owner.key,
+            recipient: accounts.recipient.key,
+            system_program: accounts.system_program.key,
+            payer: accounts.payer.key,
+            mint_stats: accounts.mint_stats.key,
+            owner_registry: accounts.owner_registry.map(|a| a.key),
+            withholding_token_account: accounts.withholding_token_account.map(|a| a.key),
+        },
+        params,
+    );
+    let mut account_infos = vec![
+        token_vesting_program.clone(),
+        accounts.spl_token_program.clone(),
+        accounts.vesting_contract.clone(),
+        accounts.vault.clone(),
+        accounts.source_tokens.clone(),
+        accounts.source_tokens_owner.clone(),
+        accounts.recipient.clone(),
+        accounts.system_program.clone(),
+        accounts.payer.clone(),
+        accounts.mint_stats.clone(),
+    ];
+    account_infos.extend(accounts.owner_registry.cloned());
+    account_infos.extend(accounts.withholding_token_account.cloned());
+    invoke_signed(&ix, &account_infos, signer_seeds)
+}
+
+#[allow(missing_docs)]
+pub fn claim<'a>(
+    token_vesting_program: &AccountInfo<'a>,
+    accounts: instruction::claim::Accounts<AccountInfo<'a>>,
+    params: instruction::claim::Params,
+    signer_seeds: &[&[&[u8]]],
+) -> ProgramResult {
+    let extra_destination_token_accounts = keys(accounts.extra_destination_token_accounts);
+    let co_signers = keys(accounts.co_signers);
+    let ix = instruction::claim(
+        instruction::claim::Accounts {
+            spl_token_program: accounts.spl_token_program.key,
+            vesting_contract: accounts.vesting_contract.key,
+            vesting_contract_signer: accounts.vesting_contract_signer.key,
+            vault: accounts.vault.key,
+            destination_token_account: accounts.destination_token_account.key,
+            owner: accounts.owner.key,
+            mint_stats: accounts.mint_stats.map(|a| a.key),
+            extra_destination_token_accounts: &extra_destination_token_accounts,
+            co_signers: &co_signers,
+        },
+        params,
+    );
+    let mut account_infos = vec![
+        token_vesting_program.clone(),
+        accounts.spl_token_program.clone(),
+        accounts.vesting_contract.clone(),
+        accounts.vesting_contract_signer.clone(),
+        accounts.vault.clone(),
+        accounts.destination_token_account.clone(),
+        accounts.owner.clone(),
+    ];
+    account_infos.extend(accounts.mint_stats.cloned());
+    account_infos.extend_from_slice(accounts.extra_destination_token_accounts);
+    account_infos.extend_from_slice(accounts.co_signers);
+    invoke_signed(&ix, &account_infos, signer_seeds)
+}
+
+#[allow(missing_docs)]
+pub fn accelerate<'a>(
+    token_vesting_program: &AccountInfo<'a>,
+    accounts: instruction::accelerate::Accounts<AccountInfo<'a>>,
+    params: instruction::accelerate::Params,
+    signer_seeds: &[&[&[u8]]],
+) -> ProgramResult {
+    let co_signers = keys(accounts.co_signers);
+    let ix = instruction::accelerate(
+        instruction::accelerate::Accounts {
+            vesting_contract: accounts.vesting_contract.key,
+            grantor: accounts.grantor.key,
+            co_signers: &co_signers,
+        },
+        params,
+    );
+    let mut account_infos = vec![
+        token_vesting_program.clone(),
+        accounts.vesting_contract.clone(),
+        accounts.grantor.clone(),
+    ];
+    account_infos.extend_from_slice(accounts.co_signers);
+    invoke_signed(&ix, &account_infos, signer_seeds)
+}
+
+/// Evaluate a vesting contract and read back the resulting `VestingStatus`
+pub fn get_claimable<'a>(
+    token_vesting_program: &AccountInfo<'a>,
+    accounts: instruction::get_claimable::Accounts<AccountInfo<'a>>,
+) -> Result<instruction::get_claimable::VestingStatus, ProgramError> {
+    let ix = instruction::get_claimable(
+        instruction::get_claimable::Accounts {
+            vesting_contract: accounts.vesting_contract.key,
+        },
+        instruction::get_claimable::Params {},
+    );
+    invoke_signed(
+        &ix,
+        &[
+            token_vesting_program.clone(),
+            accounts.vesting_contract.clone(),
+        ],
+        &[],
+    )?;
+    instruction::get_claimable::read_return_data()
+}
+
+#[allow(missing_docs)]
+pub fn migrate<'a>(
+    token_vesting_program: &AccountInfo<'a>,
+    accounts: instruction::migrate::Accounts<AccountInfo<'a>>,
+    params: instruction::migrate::Params,
+    signer_seeds: &[&[&[u8]]],
+) -> ProgramResult {
+    let ix = instruction::migrate(
+        instruction::migrate::Accounts {
+            system_program: accounts.system_program.key,
+            vesting_contract: accounts.vesting_contract.key,
+            vault: accounts.vault.key,
+            payer: accounts.payer.key,
+        },
+        params,
+    );
+    invoke_signed(
+        &ix,
+        &[
+            token_vesting_program.clone(),
+            accounts.system_program.clone(),
+            accounts.vesting_contract.clone(),
+            accounts.vault.clone(),
+            accounts.payer.clone(),
+        ],
+        signer_seeds,
+    )
+}
+
+#[allow(missing_docs)]
+pub fn set_metadata<'a>(
+    token_vesting_program: &AccountInfo<'a>,
+    accounts: instruction::set_metadata::Accounts<AccountInfo<'a>>,
+    params: instruction::set_metadata::Params,
+    signer_seeds: &[&[&[u8]]],
+) -> ProgramResult {
+    let co_signers = keys(accounts.co_signers);
+    let ix = instruction::set_metadata(
+        instruction::set_metadata::Accounts {
+            vesting_contract: accounts.vesting_contract.key,
+            grantor: accounts.grantor.key,
+            co_signers: &co_signers,
+        },
+        params,
+    );
+    let mut account_infos = vec![
+        token_vesting_program.clone(),
+        accounts.vesting_contract.clone(),
+        accounts.grantor.clone(),
+    ];
+    account_infos.extend_from_slice(accounts.co_signers);
+    invoke_signed(&ix, &account_infos, signer_seeds)
+}
+
+#[allow(missing_docs)]
+pub fn activate<'a>(
+    token_vesting_program: &AccountInfo<'a>,
+    accounts: instruction::activate::Accounts<AccountInfo<'a>>,
+    params: instruction::activate::Params,
+    signer_seeds: &[&[&[u8]]],
+) -> ProgramResult {
+    let ix = instruction::activate(
+        instruction::activate::Accounts {
+            vesting_contract: accounts.vesting_contract.key,
+            activator: accounts.activator.key,
+        },
+        params,
+    );
+    invoke_signed(
+        &ix,
+        &[
+            token_vesting_program.clone(),
+            accounts.vesting_contract.clone(),
+            accounts.activator.clone(),
+        ],
+        signer_seeds,
+    )
+}
+
+#[allow(missing_docs)]
+pub fn sweep_excess<'a>(
+    token_vesting_program: &AccountInfo<'a>,
+    accounts: instruction::sweep_excess::Accounts<AccountInfo<'a>>,
+    params: instruction::sweep_excess::Params,
+    signer_seeds: &[&[&[u8]]],
+) -> ProgramResult {
+    let co_signers = keys(accounts.co_signers);
+    let ix = instruction::sweep_excess(
+        instruction::sweep_excess::Accounts {
+            spl_token_program: accounts.spl_token_program.key,
+            vesting_contract: accounts.vesting_contract.key,
+            vesting_contract_signer: accounts.vesting_contract_signer.key,
+            vault: accounts.vault.key,
+            destination_token_account: accounts.destination_token_account.key,
+            grantor: accounts.grantor.key,
+            mint_stats: accounts.mint_stats.map(|a| a.key),
+            co_signers: &co_signers,
+        },
+        params,
+    );
+    let mut account_infos = vec![
+        token_vesting_program.clone(),
+        accounts.spl_token_program.clone(),
+        accounts.vesting_contract.clone(),
+        accounts.vesting_contract_signer.clone(),
+        accounts.vault.clone(),
+        accounts.destination_token_account.clone(),
+        accounts.grantor.clone(),
+    ];
+    account_infos.extend(accounts.mint_stats.cloned());
+    account_infos.extend_from_slice(accounts.co_signers);
+    invoke_signed(&ix, &account_infos, signer_seeds)
+}
+
+#[allow(missing_docs)]
+pub fn claim_to_associated<'a>(
+    token_vesting_program: &AccountInfo<'a>,
+    accounts: instruction::claim_to_associated::Accounts<AccountInfo<'a>>,
+    params: instruction::claim_to_associated::Params,
+    signer_seeds: &[&[&[u8]]],
+) -> ProgramResult {
+    let extra_destination_token_accounts = keys(accounts.extra_destination_token_accounts);
+    let co_signers = keys(accounts.co_signers);
+    let ix = instruction::claim_to_associated(
+        instruction::claim_to_associated::Accounts {
+            spl_token_program: accounts.spl_token_program.key,
+            vesting_contract: accounts.vesting_contract.key,
+            vesting_contract_signer: accounts.vesting_contract_signer.key,
+            vault: accounts.vault.key,
+            destination_token_account: accounts.destination_token_account.key,
+            owner: accounts.owner.key,
+            mint: accounts.mint.key,
+            system_program: accounts.system_program.key,
+            associated_token_program: accounts.associated_token_program.key,
+            payer: accounts.payer.key,
+            mint_stats: accounts.mint_stats.map(|a| a.key),
+            extra_destination_token_accounts: &extra_destination_token_accounts,
+            co_signers: &co_signers,
+        },
+        params,
+    );
+    let mut account_infos = vec![
+        token_vesting_program.clone(),
+        accounts.spl_token_program.clone(),
+        accounts.vesting_contract.clone(),
+        accounts.vesting_contract_signer.clone(),
+        accounts.vault.clone(),
+        accounts.destination_token_account.clone(),
+        accounts.owner.clone(),
+        accounts.mint.clone(),
+        accounts.system_program.clone(),
+        accounts.associated_token_program.clone(),
+        accounts.payer.clone(),
+    ];
+    account_infos.extend(accounts.mint_stats.cloned());
+    account_infos.extend_from_slice(accounts.extra_destination_token_accounts);
+    account_infos.extend_from_slice(accounts.co_signers);
+    invoke_signed(&ix, &account_infos, signer_seeds)
+}
+
+#[allow(missing_docs)]
+pub fn reclaim<'a>(
+    token_vesting_program: &AccountInfo<'a>,
+    accounts: instruction::reclaim::Accounts<AccountInfo<'a>>,
+    params: instruction::reclaim::Params,
+    signer_seeds: &[&[&[u8]]],
+) -> ProgramResult {
+    let co_signers = keys(accounts.co_signers);
+    let ix = instruction::reclaim(
+        instruction::reclaim::Accounts {
+            spl_token_program: accounts.spl_token_program.key,
+            vesting_contract: accounts.vesting_contract.key,
+            vesting_contract_signer: accounts.vesting_contract_signer.key,
+            vault: accounts.vault.key,
+            destination_token_account: accounts.destination_token_account.key,
+            grantor: accounts.grantor.key,
+            mint_stats: accounts.mint_stats.map(|a| a.key),
+            co_signers: &co_signers,
+        },
+        params,
+    );
+    let mut account_infos = vec![
+        token_vesting_program.clone(),
+        accounts.spl_token_program.clone(),
+        accounts.vesting_contract.clone(),
+        accounts.vesting_contract_signer.clone(),
+        accounts.vault.clone(),
+        accounts.destination_token_account.clone(),
+        accounts.grantor.clone(),
+    ];
+    account_infos.extend(accounts.mint_stats.cloned());
+    account_infos.extend_from_slice(accounts.co_signers);
+    invoke_signed(&ix, &account_infos, signer_seeds)
+}
+
+#[allow(missing_docs)]
+pub fn set_split_destinations<'a>(
+    token_vesting_program: &AccountInfo<'a>,
+    accounts: instruction::set_split_destinations::Accounts<AccountInfo<'a>>,
+    params: instruction::set_split_destinations::Params,
+    signer_seeds: &[&[&[u8]]],
+) -> ProgramResult {
+    let co_signers = keys(accounts.co_signers);
+    let ix = instruction::set_split_destinations(
+        instruction::set_split_destinations::Accounts {
+            vesting_contract: accounts.vesting_contract.key,
+            owner: accounts.owner.key,
+            co_signers: &co_signers,
+        },
+        params,
+    );
+    let mut account_infos = vec![
+        token_vesting_program.clone(),
+        accounts.vesting_contract.clone(),
+        accounts.owner.clone(),
+    ];
+    account_infos.extend_from_slice(accounts.co_signers);
+    invoke_signed(&ix, &account_infos, signer_seeds)
+}
+
+#[allow(missing_docs)]
+pub fn approve_milestone<'a>(
+    token_vesting_program: &AccountInfo<'a>,
+    accounts: instruction::approve_milestone::Accounts<AccountInfo<'a>>,
+    params: instruction::approve_milestone::Params,
+    signer_seeds: &[&[&[u8]]],
+) -> ProgramResult {
+    let ix = instruction::approve_milestone(
+        instruction::approve_milestone::Accounts {
+            vesting_contract: accounts.vesting_contract.key,
+            approver: accounts.approver.key,
+        },
+        params,
+    );
+    invoke_signed(
+        &ix,
+        &[
+            token_vesting_program.clone(),
+            accounts.vesting_contract.clone(),
+            accounts.approver.clone(),
+        ],
+        signer_seeds,
+    )
+}
+
+#[allow(missing_docs)]
+pub fn freeze<'a>(
+    token_vesting_program: &AccountInfo<'a>,
+    accounts: instruction::freeze::Accounts<AccountInfo<'a>>,
+    params: instruction::freeze::Params,
+    signer_seeds: &[&[&[u8]]],
+) -> ProgramResult {
+    let ix = instruction::freeze(
+        instruction::freeze::Accounts {
+            vesting_contract: accounts.vesting_contract.key,
+            freeze_authority: accounts.freeze_authority.key,
+        },
+        params,
+    );
+    invoke_signed(
+        &ix,
+        &[
+            token_vesting_program.clone(),
+            accounts.vesting_contract.clone(),
+            accounts.freeze_authority.clone(),
+        ],
+        signer_seeds,
+    )
+}
+
+#[allow(missing_docs)]
+pub fn thaw<'a>(
+    token_vesting_program: &AccountInfo<'a>,
+    accounts: instruction::thaw::Accounts<AccountInfo<'a>>,
+    params: instruction::thaw::Params,
+    signer_seeds: &[&[&[u8]]],
+) -> ProgramResult {
+    let ix = instruction::thaw(
+        instruction::thaw::Accounts {
+            vesting_contract: accounts.vesting_contract.key,
+            freeze_authority: accounts.freeze_authority.key,
+        },
+        params,
+    );
+    invoke_signed(
+        &ix,
+        &[
+            token_vesting_program.clone(),
+            accounts.vesting_contract.clone(),
+            accounts.freeze_authority.clone(),
+        ],
+        signer_seeds,
+    )
+}
+
+/// The keys of a slice of accounts, for the trailing accounts of an instruction
+fn keys(accounts: &[AccountInfo]) -> Vec<Pubkey> {
+    accounts.iter().map(|a| *a.key).collect()
+}
@@ -43,6 +43,186 @@ impl PrintProgramError for TokenVestingError {
             TokenVestingError::InvalidVaultAccount => {
                 msg!("Error: The provided vault account is invalid")
             }
+            TokenVestingError::WrongContractOwner => {
+                msg!("Error: The signer is not the owner of the vesting contract")
+            }
+            TokenVestingError::WrongVault => {
+                msg!("Error: The provided vault is not the vault of the vesting contract")
+            }
+            TokenVestingError::WrongContractSigner => {
+                msg!("Error: The provided vesting contract signer is invalid")
+            }
+            TokenVestingError::UnsortedSchedule => {
+                msg!("Error: The schedules should be provided in order")
+            }
+            TokenVestingError::WrongAccountSize => {
+                msg!("Error: The vesting contract account is incorrectly sized for the supplied schedule")
+            }
+            TokenVestingError::Overflow => msg!("Error: An arithmetic operation overflowed"),
+            TokenVestingError::NothingToClaim => {
+                msg!("Error: There are no unlocked tokens to claim")
+            }
+            TokenVestingError::WrongGrantor => {
+                msg!("Error: The signer is not the grantor of the vesting contract")
+            }
+            TokenVestingError::InvalidAccelerationFraction => {
+                msg!("Error: The acceleration fraction should be between 1 and 10000 basis points")
+            }
+            TokenVestingError::NothingToAccelerate => {
+                msg!("Error: There are no remaining schedules to accelerate")
+            }
+            TokenVestingError::AccelerationDelaysUnlock => {
+                msg!("Error: The acceleration would delay an unlock")
+            }
+            TokenVestingError::AlreadyMigrated => {
+                msg!("Error: The vesting contract has already been migrated")
+            }
+            TokenVestingError::InvalidReturnData => {
+                msg!("Error: No vesting status was found in the return data")
+            }
+            TokenVestingError::InvalidInstructionDataLength => {
+                msg!("Error: The instruction data has an invalid length")
+            }
+            TokenVestingError::MisalignedInstructionData => {
+                msg!("Error: The instruction data is misaligned")
+            }
+            TokenVestingError::InvalidSignerNonce => {
+                msg!("Error: The signer nonce should fit in a single byte")
+            }
+            TokenVestingError::ContractFullyClaimed => {
+                msg!("Error: Every schedule of the vesting contract has already been claimed")
+            }
+            TokenVestingError::EmptySchedule => {
+                msg!("Error: The schedule should contain at least one item")
+            }
+            TokenVestingError::ZeroQuantitySchedule => {
+                msg!("Error: The schedule items should have a non-zero quantity")
+            }
+            TokenVestingError::UnlockInThePast => {
+                msg!("Error: The schedule contains unlocks in the past")
+            }
+            TokenVestingError::InvalidRecipient => {
+                msg!("Error: The recipient of the vesting contract is invalid")
+            }
+            TokenVestingError::MintMismatch => {
+                msg!("Error: The source tokens and the vault should have the same mint")
+            }
+            TokenVestingError::InvalidCreateFlags => {
+                msg!("Error: The create flags are invalid")
+            }
+            TokenVestingError::LegacyLayout => {
+                msg!("Error: The vesting contract should be migrated to the current layout first")
+            }
+            TokenVestingError::InvalidMetadata => {
+                msg!("Error: The label and URI should be zero-padded UTF-8 strings which fit in the contract")
+            }
+            TokenVestingError::NotActivated => {
+                msg!("Error: The vesting contract has not been activated yet")
+            }
+            TokenVestingError::AlreadyActivated => {
+                msg!("Error: The vesting contract has already been activated")
+            }
+            TokenVestingError::WrongActivator => {
+                msg!("Error: The signer is not the activator of the vesting contract")
+            }
+            TokenVestingError::InvalidActivationTimestamp => {
+                msg!("Error: The activation timestamp should be non-zero and keep every unlock within range")
+            }
+            TokenVestingError::InvalidAccelerationTimestamp => {
+                msg!("Error: The acceleration should not move unlocks before the activation")
+            }
+            TokenVestingError::NoExcessTokens => {
+                msg!("Error: The vault holds no tokens beyond the remaining schedules")
+            }
+            TokenVestingError::InvalidSweepRecipient => {
+                msg!("Error: The sweep recipient should be the grantor or the owner")
+            }
+            TokenVestingError::WrongDestinationOwner => {
+                msg!("Error: The destination token account is not owned by the expected account")
+            }
+            TokenVestingError::InvalidAssociatedTokenAccount => {
+                msg!("Error: The destination is not the associated token account of the owner")
+            }
+            TokenVestingError::InvalidClaimFlags => {
+                msg!("Error: The claim flags contain unknown values")
+            }
+            TokenVestingError::NotNativeMint => {
+                msg!("Error: Only tokens of the native mint can be unwrapped")
+            }
+            TokenVestingError::NativeAccountNotEmpty => {
+                msg!("Error: Only an associated token account holding the claimed tokens alone can be unwrapped")
+            }
+            TokenVestingError::InvalidExpiry => {
+                msg!("Error: The expiry should follow the current time and every unlock")
+            }
+            TokenVestingError::NotExpirable => {
+                msg!("Error: The vesting contract has no expiry")
+            }
+            TokenVestingError::NotExpired => {
+                msg!("Error: The vesting contract has not expired yet")
+            }
+            TokenVestingError::ContractExpired => {
+                msg!("Error: The vesting contract has expired")
+            }
+            TokenVestingError::WrongSplitDestinations => {
+                msg!("Error: The destination accounts do not match the registered split")
+            }
+            TokenVestingError::InvalidSplit => {
+                msg!("Error: The split destinations should be distinct and add up to 10000 basis points")
+            }
+            TokenVestingError::InvalidWithholding => {
+                msg!("Error: Invalid withholding rate or account")
+            }
+            TokenVestingError::WrongWithholdingAccount => {
+                msg!("Error: The withholding token account is missing or wrong")
+            }
+            TokenVestingError::BonusOnWithheldContract => {
+                msg!("Error: Excess tokens cannot be granted as a bonus when releases are withheld")
+            }
+            TokenVestingError::InvalidMilestones => {
+                msg!("Error: Invalid milestone approver or gated schedule entries")
+            }
+            TokenVestingError::WrongMilestoneApprover => {
+                msg!("Error: The milestone approver is wrong")
+            }
+            TokenVestingError::NotMilestoneGated => {
+                msg!("Error: The schedule entry isn't gated by a milestone")
+            }
+            TokenVestingError::MilestoneAlreadyApproved => {
+                msg!("Error: The milestone was already approved")
+            }
+            TokenVestingError::InvalidSignerSet => {
+                msg!("Error: Invalid signer set")
+            }
+            TokenVestingError::NotEnoughSignatures => {
+                msg!("Error: Not enough keys of the signer set signed")
+            }
+            TokenVestingError::SignerSetCannotUnwrap => {
+                msg!("Error: Claims of an owner signer set cannot be unwrapped")
+            }
+            TokenVestingError::WrongFreezeAuthority => {
+                msg!("Error: The freeze authority is missing or wrong")
+            }
+            TokenVestingError::ContractFrozen => {
+                msg!("Error: The contract is frozen")
+            }
+            TokenVestingError::NotFrozen => {
+                msg!("Error: The contract isn't frozen")
+            }
+            TokenVestingError::InvalidThawFlags => {
+                msg!("Error: Invalid thaw flags")
+            }
+            TokenVestingError::WrongMintStats => {
+                msg!(
+                    "Error: The mint statistics account is not the PDA of the mint of the contract"
+                )
+            }
+            TokenVestingError::WrongOwnerRegistry => {
+                msg!("Error: The owner registry account is not the PDA of the recipient")
+            }
+            TokenVestingError::NonCanonicalSignerNonce => {
+                msg!("Error: The signer nonce should be the canonical bump of the vesting contract signer")
+            }
         }
     }
 }
@@ -1,21 +1,145 @@
 use {
+    bytemuck::PodCastError,
     num_derive::FromPrimitive,
+    num_traits::FromPrimitive,
     solana_program::{decode_error::DecodeError, program_error::ProgramError},
     thiserror::Error,
 };
 
-#[derive(Clone, Debug, Error, FromPrimitive)]
+/// The errors of the program. The numeric codes are stable: variants must only ever be appended.
+#[derive(Clone, Debug, Error, FromPrimitive, PartialEq, Eq)]
 pub enum TokenVestingError {
     #[error("This account is already initialized")]
-    AlreadyInitialized,
+    AlreadyInitialized = 0,
     #[error("Data type mismatch")]
-    DataTypeMismatch,
+    DataTypeMismatch = 1,
     #[error("Wrong account owner")]
-    WrongOwner,
+    WrongOwner = 2,
     #[error("Account is uninitialized")]
-    Uninitialized,
+    Uninitialized = 3,
     #[error("The provided vault account is invalid")]
-    InvalidVaultAccount,
+    InvalidVaultAccount = 4,
+    #[error("The signer is not the owner of the vesting contract")]
+    WrongContractOwner = 5,
+    #[error("The provided vault is not the vault of the vesting contract")]
+    WrongVault = 6,
+    #[error("The provided vesting contract signer is invalid")]
+    WrongContractSigner = 7,
+    #[error("The schedules should be provided in order")]
+    UnsortedSchedule = 8,
+    #[error("The vesting contract account is incorrectly sized for the supplied schedule")]
+    WrongAccountSize = 9,
+    #[error("An arithmetic operation overflowed")]
+    Overflow = 10,
+    #[error("There are no unlocked tokens to claim")]
+    NothingToClaim = 11,
+    #[error("The signer is not the grantor of the vesting contract")]
+    WrongGrantor = 12,
+    #[error("The acceleration fraction should be between 1 and 10000 basis points")]
+    InvalidAccelerationFraction = 13,
+    #[error("There are no remaining schedules to accelerate")]
+    NothingToAccelerate = 14,
+    #[error("The acceleration would delay an unlock")]
+    AccelerationDelaysUnlock = 15,
+    #[error("The vesting contract has already been migrated")]
+    AlreadyMigrated = 16,
+    #[error("No vesting status was found in the return data")]
+    InvalidReturnData = 17,
+    #[error("The instruction data has an invalid length")]
+    InvalidInstructionDataLength = 18,
+    #[error("The instruction data is misaligned")]
+    MisalignedInstructionData = 19,
+    #[error("The signer nonce should fit in a single byte")]
+    InvalidSignerNonce = 20,
+    #[error("Every schedule of the vesting contract has already been claimed")]
+    ContractFullyClaimed = 21,
+    #[error("The schedule should contain at least one item")]
+    EmptySchedule = 22,
+    #[error("The schedule items should have a non-zero quantity")]
+    ZeroQuantitySchedule = 23,
+    #[error("The schedule contains unlocks in the past")]
+    UnlockInThePast = 24,
+    #[error("The recipient of the vesting contract is invalid")]
+    InvalidRecipient = 25,
+    #[error("The source tokens and the vault should have the same mint")]
+    MintMismatch = 26,
+    // 27 is reserved, it was returned by the rent exemption check replaced by the rent top-up
+    #[error("The create flags are invalid")]
+    InvalidCreateFlags = 28,
+    #[error("The signer nonce should be the canonical bump of the vesting contract signer")]
+    NonCanonicalSignerNonce = 29,
+    #[error("The vesting contract should be migrated to the current layout first")]
+    LegacyLayout = 30,
+    #[error("The label and URI should be zero-padded UTF-8 strings which fit in the contract")]
+    InvalidMetadata = 31,
+    #[error("The vesting contract has not been activated yet")]
+    NotActivated = 32,
+    #[error("The vesting contract has already been activated")]
+    AlreadyActivated = 33,
+    #[error("The signer is not the activator of the vesting contract")]
+    WrongActivator = 34,
+    #[error("The activation timestamp should be non-zero and keep every unlock within range")]
+    InvalidActivationTimestamp = 35,
+    #[error("The acceleration should not move unlocks before the activation")]
+    InvalidAccelerationTimestamp = 36,
+    #[error("The vault holds no tokens beyond the remaining schedules")]
+    NoExcessTokens = 37,
+    #[error("The sweep recipient should be the grantor or the owner")]
+    InvalidSweepRecipient = 38,
+    #[error("The destination token account is not owned by the expected account")]
+    WrongDestinationOwner = 39,
+    #[error("The destination is not the associated token account of the owner")]
+    InvalidAssociatedTokenAccount = 40,
+    #[error("The claim flags contain unknown values")]
+    InvalidClaimFlags = 41,
+    #[error("Only tokens of the native mint can be unwrapped")]
+    NotNativeMint = 42,
+    #[error("Only an associated token account holding the claimed tokens alone can be unwrapped")]
+    NativeAccountNotEmpty = 43,
+    #[error("The expiry should follow the current time and every unlock")]
+    InvalidExpiry = 44,
+    #[error("The vesting contract has no expiry")]
+    NotExpirable = 45,
+    #[error("The vesting contract has not expired yet")]
+    NotExpired = 46,
+    #[error("The vesting contract has expired")]
+    ContractExpired = 47,
+    #[error("The destination accounts do not match the registered split")]
+    WrongSplitDestinations = 48,
+    #[error("The split destinations should be distinct and add up to 10000 basis points")]
+    InvalidSplit = 49,
+    #[error("Invalid withholding rate or account")]
+    InvalidWithholding = 50,
+    #[error("The withholding token account is missing or wrong")]
+    WrongWithholdingAccount = 51,
+    #[error("Excess tokens cannot be granted as a bonus when releases are withheld")]
+    BonusOnWithheldContract = 52,
+    #[error("Invalid milestone approver or gated schedule entries")]
+    InvalidMilestones = 53,
+    #[error("The milestone approver is wrong")]
+    WrongMilestoneApprover = 54,
+    #[error("The schedule entry isn't gated by a milestone")]
+    NotMilestoneGated = 55,
+    #[error("The milestone was already approved")]
+    MilestoneAlreadyApproved = 56,
+    #[error("Invalid signer set")]
+    InvalidSignerSet = 57,
+    #[error("Not enough keys of the signer set signed")]
+    NotEnoughSignatures = 58,
+    #[error("Claims of an owner signer set cannot be unwrapped")]
+    SignerSetCannotUnwrap = 59,
+    #[error("The freeze authority is missing or wrong")]
+    WrongFreezeAuthority = 60,
+    #[error("The contract is frozen")]
+    ContractFrozen = 61,
+    #[error("The contract isn't frozen")]
+    NotFrozen = 62,
+    #[error("Invalid thaw flags")]
+    InvalidThawFlags = 63,
+    #[error("The mint statistics account is not the PDA of the mint of the contract")]
+    WrongMintStats = 64,
+    #[error("The owner registry account is not the PDA of the recipient")]
+    WrongOwnerRegistry = 65,
 }
 
 impl From<TokenVestingError> for ProgramError {
@@ -24,8 +148,35 @@ impl From<TokenVestingError> for ProgramError {
     }
 }
 
+impl From<PodCastError> for TokenVestingError {
+    fn from(e: PodCastError) -> Self {
+        match e {
+            PodCastError::TargetAlignmentGreaterAndInputNotAligned
+            | PodCastError::AlignmentMismatch => TokenVestingError::MisalignedInstructionData,
+            PodCastError::OutputSliceWouldHaveSlop | PodCastError::SizeMismatch => {
+                TokenVestingError::InvalidInstructionDataLength
+            }
+        }
+    }
+}
+
 impl<T> DecodeError<T> for TokenVestingError {
     fn type_of() -> &'static str {
         "TokenVestingError"
     }
 }
+
+impl TokenVestingError {
+    /// Decode the code of a `ProgramError::Custom` error returned by the program
+    pub fn from_code(code: u32) -> Option<Self> {
+        FromPrimitive::from_u32(code)
+    }
+
+    /// Decode a `ProgramError` returned by the program
+    pub fn from_program_error(error: &ProgramError) -> Option<Self> {
+        match error {
+            ProgramError::Custom(code) => Self::from_code(*code),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,277 @@
+use {
+    borsh::{BorshDeserialize, BorshSerialize},
+    solana_program::{log::sol_log_data, pubkey::Pubkey},
+};
+
+/// The version of the event encoding, written as the first byte of every event
+pub const EVENT_VERSION: u8 = 1;
+
+const PROGRAM_DATA_PREFIX: &str = "Program data: ";
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+/// Emitted when a new vesting contract is created
+pub struct CreateEvent {
+    /// The vesting contract account
+    pub vesting_contract: Pubkey,
+    /// The eventual token receiver
+    pub owner: Pubkey,
+    /// The account which funded the contract
+    pub grantor: Pubkey,
+    /// The mint of the vested tokens
+    pub mint: Pubkey,
+    /// The contract escrow vault
+    pub vault: Pubkey,
+    /// The total quantity of tokens locked in the contract
+    pub total_amount: u64,
+    /// The index of the first schedule of the contract
+    pub schedule_start_index: u64,
+    /// The index following the last schedule of the contract
+    pub schedule_end_index: u64,
+    /// The UTC timestamp of the creation
+    pub timestamp: i64,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+/// Emitted when tokens are claimed from a vesting contract
+pub struct ClaimEvent {
+    /// The vesting contract account
+    pub vesting_contract: Pubkey,
+    /// The owner of the vesting contract
+    pub owner: Pubkey,
+    /// The mint of the vested tokens
+    pub mint: Pubkey,
+    /// The token account which received the tokens
+    pub destination: Pubkey,
+    /// The quantity of tokens received by the owner
+    pub amount: u64,
+    /// The quantity of tokens transferred to the withholding account of the grantor
+    pub withheld_amount: u64,
+    /// The index of the first schedule released by the claim
+    pub schedule_start_index: u64,
+    /// The index following the last schedule released by the claim
+    pub schedule_end_index: u64,
+    /// The UTC timestamp of the claim
+    pub timestamp: i64,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+/// Emitted when the grantor accelerates a vesting contract
+pub struct AccelerateEvent {
+    /// The vesting contract account
+    pub vesting_contract: Pubkey,
+    /// The grantor of the vesting contract
+    pub grantor: Pubkey,
+    /// The new unlock timestamp of the accelerated schedules
+    pub unlock_timestamp: u64,
+    /// The index of the first accelerated schedule
+    pub schedule_start_index: u64,
+    /// The index following the last accelerated schedule
+    pub schedule_end_index: u64,
+    /// The UTC timestamp of the acceleration
+    pub timestamp: i64,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+/// Emitted when a vesting contract account is upgraded to a new layout
+pub struct MigrateEvent {
+    /// The vesting contract account
+    pub vesting_contract: Pubkey,
+    /// The layout version of the account after the migration
+    pub version: u8,
+    /// The UTC timestamp of the migration
+    pub timestamp: i64,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+/// Emitted when the label and metadata URI of a vesting contract are set
+pub struct MetadataEvent {
+    /// The vesting contract account
+    pub vesting_contract: Pubkey,
+    /// The label of the contract, empty if it isn't set
+    pub label: String,
+    /// The metadata URI of the contract, empty if it isn't set
+    pub uri: String,
+    /// The UTC timestamp of the update
+    pub timestamp: i64,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+/// Emitted when a vesting contract with a deferred activation is activated
+pub struct ActivateEvent {
+    /// The vesting contract account
+    pub vesting_contract: Pubkey,
+    /// The activator of the vesting contract
+    pub activator: Pubkey,
+    /// The UTC timestamp the unlock offsets of the schedules are relative to
+    pub activation_timestamp: u64,
+    /// The UTC timestamp of the activation
+    pub timestamp: i64,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+/// Emitted when the grantor withdraws the excess tokens of a vault
+pub struct SweepEvent {
+    /// The vesting contract account
+    pub vesting_contract: Pubkey,
+    /// The grantor of the vesting contract
+    pub grantor: Pubkey,
+    /// The mint of the vested tokens
+    pub mint: Pubkey,
+    /// The token account which received the excess tokens
+    pub destination: Pubkey,
+    /// The quantity of tokens withdrawn from the vault
+    pub amount: u64,
+    /// Whether the tokens were sent to the owner as a vested bonus
+    pub is_bonus: bool,
+    /// The UTC timestamp of the sweep
+    pub timestamp: i64,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+/// Emitted when the grantor reclaims the vault of an expired vesting contract
+pub struct ReclaimEvent {
+    /// The vesting contract account
+    pub vesting_contract: Pubkey,
+    /// The grantor of the vesting contract
+    pub grantor: Pubkey,
+    /// The mint of the vested tokens
+    pub mint: Pubkey,
+    /// The token account which received the unclaimed tokens
+    pub destination: Pubkey,
+    /// The quantity of tokens returned to the grantor
+    pub amount: u64,
+    /// The UTC timestamp of the reclaim
+    pub timestamp: i64,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+/// Emitted when the owner registers the destinations each release is divided between
+pub struct SplitEvent {
+    /// The vesting contract account
+    pub vesting_contract: Pubkey,
+    /// The owner of the vesting contract
+    pub owner: Pubkey,
+    /// The token accounts and their shares in basis points, empty when releases aren't divided
+    pub destinations: Vec<(Pubkey, u64)>,
+    /// The UTC timestamp of the update
+    pub timestamp: i64,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+/// Emitted when the approver approves the milestone gating a schedule entry
+pub struct MilestoneEvent {
+    /// The vesting contract account
+    pub vesting_contract: Pubkey,
+    /// The milestone approver of the vesting contract
+    pub approver: Pubkey,
+    /// The index of the schedule entry whose milestone was approved
+    pub schedule_index: u64,
+    /// The UTC timestamp of the approval
+    pub timestamp: i64,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+/// Emitted when the freeze authority holds the releases of a vesting contract
+pub struct FreezeEvent {
+    /// The vesting contract account
+    pub vesting_contract: Pubkey,
+    /// The freeze authority of the vesting contract
+    pub freeze_authority: Pubkey,
+    /// The UTC timestamp of the start of the freeze
+    pub timestamp: i64,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+/// Emitted when the freeze authority resumes the releases of a vesting contract
+pub struct ThawEvent {
+    /// The vesting contract account
+    pub vesting_contract: Pubkey,
+    /// The freeze authority of the vesting contract
+    pub freeze_authority: Pubkey,
+    /// The duration of the freeze in seconds
+    pub frozen_duration: u64,
+    /// Whether the remaining unlocks were pushed back by the frozen duration
+    pub extend_unlocks: bool,
+    /// The UTC timestamp of the end of the freeze
+    pub timestamp: i64,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+#[allow(missing_docs)]
+/// The events emitted by the program. New variants must only ever be appended.
+pub enum Event {
+    Create(CreateEvent),
+    Claim(ClaimEvent),
+    Accelerate(AccelerateEvent),
+    Migrate(MigrateEvent),
+    Metadata(MetadataEvent),
+    Activate(ActivateEvent),
+    Sweep(SweepEvent),
+    Reclaim(ReclaimEvent),
+    Split(SplitEvent),
+    Milestone(MilestoneEvent),
+    Freeze(FreezeEvent),
+    Thaw(ThawEvent),
+}
+
+impl Event {
+    /// Log the event as program data
+    pub fn emit(&self) {
+        let mut data = vec![EVENT_VERSION];
+        self.serialize(&mut data).unwrap();
+        sol_log_data(&[&data]);
+    }
+
+    /// Decode an event from the raw bytes of a program data log
+    pub fn decode(data: &[u8]) -> Option<Self> {
+        match data.split_first() {
+            Some((&EVENT_VERSION, mut remaining)) => {
+                let event = Self::deserialize(&mut remaining).ok()?;
+                if remaining.is_empty() {
+                    Some(event)
+                } else {
+                    None
+                }
+            }
+            _ => None,
+        }
+    }
+
+    /// Extract the events emitted by the program from the log messages of a transaction
+    ///
+    /// Program data logged by other programs, including programs invoked by this one, is ignored.
+    pub fn from_logs<S: AsRef<str>>(logs: &[S]) -> Vec<Self> {
+        let program_id = crate::ID.to_string();
+        let mut invocation_stack: Vec<&str> = vec![];
+        let mut events = vec![];
+        for log in logs {
+            let log = log.as_ref();
+            if let Some(data) = log.strip_prefix(PROGRAM_DATA_PREFIX) {
+                if invocation_stack.last() != Some(&program_id.as_str()) {
+                    continue;
+                }
+                let event = data
+                    .split(' ')
+                    .next()
+                    .and_then(|d| base64::decode(d).ok())
+                    .and_then(|d| Self::decode(&d));
+                if let Some(event) = event {
+                    events.push(event);
+                }
+                continue;
+            }
+            let mut words = log.split(' ');
+            match (words.next(), words.next(), words.next()) {
+                (Some("Program"), Some(key), Some("invoke")) => invocation_stack.push(key),
+                (Some("Program"), Some(_), Some("success")) => {
+                    invocation_stack.pop();
+                }
+                (Some("Program"), Some(_), Some("failed:")) => {
+                    invocation_stack.pop();
+                }
+                _ => {}
+            }
+        }
+        events
+    }
+}
@@ -1,5 +1,17 @@
+pub use crate::processor::accelerate;
+pub use crate::processor::activate;
+pub use crate::processor::approve_milestone;
 pub use crate::processor::claim;
+pub use crate::processor::claim_to_associated;
 pub use crate::processor::create;
+pub use crate::processor::freeze;
+pub use crate::processor::get_claimable;
+pub use crate::processor::migrate;
+pub use crate::processor::reclaim;
+pub use crate::processor::set_metadata;
+pub use crate::processor::set_split_destinations;
+pub use crate::processor::sweep_excess;
+pub use crate::processor::thaw;
 use {
     bonfida_utils::InstructionsAccount,
     borsh::{BorshDeserialize, BorshSerialize},
@@ -9,15 +21,143 @@ use {
 #[allow(missing_docs)]
 #[derive(BorshDeserialize, BorshSerialize, FromPrimitive)]
 pub enum ProgramInstruction {
-    /// An example instruction //TODO
+    /// Create a vesting contract and transfer the vested tokens to its vault
+    ///
+    /// | Index | Writable | Signer | Description                          |
+    /// | ---------------------------------------------------------------- |
+    /// | 0     | ❌        | ❌      | The SPL token program account        |
+    /// | 1     | ✅        | ❌      | The vesting contract account         |
+    /// | 2     | ✅        | ❌      | The vesting contract vault           |
+    /// | 3     | ✅        | ❌      | The source token account             |
+    /// | 4     | ❌        | ✅      | The owner of the source tokens       |
+    /// | 5     | ❌        | ❌      | The recipient of the vested tokens   |
+    /// | 6     | ❌        | ❌      | The system program account           |
+    /// | 7     | ✅        | ✅      | The rent payer account               |
+    /// | 8     | ✅        | ❌      | The mint statistics account          |
+    /// | 9     | ✅        | ❌      | The optional owner registry account  |
+    /// | 10    | ❌        | ❌      | The optional withholding account     |
+    ///
+    /// The owner registry account is only passed with the `RegisterOwner` flag.
+    Create,
+    Claim,
+    /// Move the unlock timestamps of the remaining schedules earlier
+    ///
+    /// | Index | Writable | Signer | Description                   |
+    /// | --------------------------------------------------------- |
+    /// | 0     | ✅        | ❌      | The vesting contract account  |
+    /// | 1     | ❌        | ✅      | The contract grantor          |
+    /// | 2..   | ❌        | ✅      | The co-signers of the grantor |
+    Accelerate,
+    /// Write the [`VestingStatus`][get_claimable::VestingStatus] of a contract to the return data
+    ///
+    /// | Index | Writable | Signer | Description                   |
+    /// | --------------------------------------------------------- |
+    /// | 0     | ❌        | ❌      | The vesting contract account  |
+    GetClaimable,
+    /// Upgrade a legacy vesting contract account to the current layout
     ///
     /// | Index | Writable | Signer | Description                   |
     /// | --------------------------------------------------------- |
     /// | 0     | ❌        | ❌      | The system program account    |
-    /// | 1     | ❌        | ❌      | The SPL token program account |
-    /// | 2     | ✅        | ✅      | Fee payer account             |
-    Create,
-    Claim,
+    /// | 1     | ✅        | ❌      | The vesting contract account  |
+    /// | 2     | ❌        | ❌      | The vesting contract vault    |
+    /// | 3     | ✅        | ✅      | The rent payer account        |
+    Migrate,
+    /// Replace the label and metadata URI of a vesting contract
+    ///
+    /// | Index | Writable | Signer | Description                   |
+    /// | --------------------------------------------------------- |
+    /// | 0     | ✅        | ❌      | The vesting contract account  |
+    /// | 1     | ❌        | ✅      | The contract grantor          |
+    /// | 2..   | ❌        | ✅      | The co-signers of the grantor |
+    SetMetadata,
+    /// Set the timestamp the schedules of a contract with a deferred activation are relative to
+    ///
+    /// | Index | Writable | Signer | Description                   |
+    /// | --------------------------------------------------------- |
+    /// | 0     | ✅        | ❌      | The vesting contract account  |
+    /// | 1     | ❌        | ✅      | The contract activator        |
+    Activate,
+    /// Withdraw the tokens of the vault which exceed the remaining schedules
+    ///
+    /// | Index | Writable | Signer | Description                          |
+    /// | ---------------------------------------------------------------- |
+    /// | 0     | ❌        | ❌      | The SPL token program account        |
+    /// | 1     | ✅        | ❌      | The vesting contract account         |
+    /// | 2     | ❌        | ❌      | The vesting contract signer          |
+    /// | 3     | ✅        | ❌      | The vesting contract vault           |
+    /// | 4     | ✅        | ❌      | The destination token account        |
+    /// | 5     | ❌        | ✅      | The contract grantor                 |
+    /// | 6     | ✅        | ❌      | The optional mint statistics account |
+    /// | 7..   | ❌        | ✅      | The co-signers of the grantor        |
+    ///
+    /// The mint statistics account is only passed for contracts counted in the statistics.
+    SweepExcess,
+    /// Claim the unlocked tokens to the associated token account of the owner, which is created
+    /// if it doesn't exist, and optionally unwrap them when they are wrapped SOL
+    ///
+    /// | Index | Writable | Signer | Description                          |
+    /// | ---------------------------------------------------------------- |
+    /// | 0     | ❌        | ❌      | The SPL token program account        |
+    /// | 1     | ✅        | ❌      | The vesting contract account         |
+    /// | 2     | ❌        | ❌      | The vesting contract signer          |
+    /// | 3     | ✅        | ❌      | The vesting contract vault           |
+    /// | 4     | ✅        | ❌      | The owner's associated token account |
+    /// | 5     | ✅        | ✅      | The contract owner                   |
+    /// | 6     | ❌        | ❌      | The mint of the vested tokens        |
+    /// | 7     | ❌        | ❌      | The system program account           |
+    /// | 8     | ❌        | ❌      | The associated token program account |
+    /// | 9     | ✅        | ✅      | The account creation payer           |
+    /// | 10    | ✅        | ❌      | The optional mint statistics account |
+    /// | 11..  | ✅        | ❌      | The withholding and split accounts   |
+    /// | ..    | ❌        | ✅      | The co-signers of the owner          |
+    ///
+    /// The mint statistics account is only passed for contracts counted in the statistics.
+    ClaimToAssociated,
+    /// Return every token left in the vault of an expired contract to the grantor
+    ///
+    /// | Index | Writable | Signer | Description                          |
+    /// | ---------------------------------------------------------------- |
+    /// | 0     | ❌        | ❌      | The SPL token program account        |
+    /// | 1     | ✅        | ❌      | The vesting contract account         |
+    /// | 2     | ❌        | ❌      | The vesting contract signer          |
+    /// | 3     | ✅        | ❌      | The vesting contract vault           |
+    /// | 4     | ✅        | ❌      | The grantor's token account          |
+    /// | 5     | ❌        | ✅      | The contract grantor                 |
+    /// | 6     | ✅        | ❌      | The optional mint statistics account |
+    /// | 7..   | ❌        | ✅      | The co-signers of the grantor        |
+    ///
+    /// The mint statistics account is only passed for contracts counted in the statistics.
+    Reclaim,
+    /// Register the token accounts each release is divided between
+    ///
+    /// | Index | Writable | Signer | Description                   |
+    /// | --------------------------------------------------------- |
+    /// | 0     | ✅        | ❌      | The vesting contract account  |
+    /// | 1     | ❌        | ✅      | The contract owner            |
+    /// | 2..   | ❌        | ✅      | The co-signers of the owner   |
+    SetSplitDestinations,
+    /// Approve the milestone gating an entry of the schedule, which is released once unlocked
+    ///
+    /// | Index | Writable | Signer | Description                   |
+    /// | --------------------------------------------------------- |
+    /// | 0     | ✅        | ❌      | The vesting contract account  |
+    /// | 1     | ❌        | ✅      | The milestone approver        |
+    ApproveMilestone,
+    /// Hold the releases of a contract until it is thawed
+    ///
+    /// | Index | Writable | Signer | Description                   |
+    /// | --------------------------------------------------------- |
+    /// | 0     | ✅        | ❌      | The vesting contract account  |
+    /// | 1     | ❌        | ✅      | The freeze authority          |
+    Freeze,
+    /// Resume the releases of a frozen contract, optionally pushing back its unlocks
+    ///
+    /// | Index | Writable | Signer | Description                   |
+    /// | --------------------------------------------------------- |
+    /// | 0     | ✅        | ❌      | The vesting contract account  |
+    /// | 1     | ❌        | ✅      | The freeze authority          |
+    Thaw,
 }
 #[allow(missing_docs)]
 pub fn create(accounts: create::Accounts<Pubkey>, params: create::Params) -> Instruction {
@@ -27,3 +167,84 @@ pub fn create(accounts: create::Accounts<Pubkey>, params: create::Params) -> Ins
 pub fn claim(accounts: claim::Accounts<Pubkey>, params: claim::Params) -> Instruction {
     accounts.get_instruction_cast(crate::ID, ProgramInstruction::Claim as u8, params)
 }
+#[allow(missing_docs)]
+pub fn accelerate(
+    accounts: accelerate::Accounts<Pubkey>,
+    params: accelerate::Params,
+) -> Instruction {
+    accounts.get_instruction_cast(crate::ID, ProgramInstruction::Accelerate as u8, params)
+}
+#[allow(missing_docs)]
+pub fn get_claimable(
+    accounts: get_claimable::Accounts<Pubkey>,
+    params: get_claimable::Params,
+) -> Instruction {
+    accounts.get_instruction_cast(crate::ID, ProgramInstruction::GetClaimable as u8, params)
+}
+#[allow(missing_docs)]
+pub fn migrate(accounts: migrate::Accounts<Pubkey>, params: migrate::Params) -> Instruction {
+    accounts.get_instruction_cast(crate::ID, ProgramInstruction::Migrate as u8, params)
+}
+#[allow(missing_docs)]
+pub fn set_metadata(
+    accounts: set_metadata::Accounts<Pubkey>,
+    params: set_metadata::Params,
+) -> Instruction {
+    accounts.get_instruction_cast(crate::ID, ProgramInstruction::SetMetadata as u8, params)
+}
+#[allow(missing_docs)]
+pub fn activate(accounts: activate::Accounts<Pubkey>, params: activate::Params) -> Instruction {
+    accounts.get_instruction_cast(crate::ID, ProgramInstruction::Activate as u8, params)
+}
+#[allow(missing_docs)]
+pub fn sweep_excess(
+    accounts: sweep_excess::Accounts<Pubkey>,
+    params: sweep_excess::Params,
+) -> Instruction {
+    accounts.get_instruction_cast(crate::ID, ProgramInstruction::SweepExcess as u8, params)
+}
+#[allow(missing_docs)]
+pub fn claim_to_associated(
+    accounts: claim_to_associated::Accounts<Pubkey>,
+    params: claim_to_associated::Params,
+) -> Instruction {
+    accounts.get_instruction_cast(
+        crate::ID,
+        ProgramInstruction::ClaimToAssociated as u8,
+        params,
+    )
+}
+#[allow(missing_docs)]
+pub fn reclaim(accounts: reclaim::Accounts<Pubkey>, params: reclaim::Params) -> Instruction {
+    accounts.get_instruction_cast(crate::ID, ProgramInstruction::Reclaim as u8, params)
+}
+#[allow(missing_docs)]
+pub fn set_split_destinations(
+    accounts: set_split_destinations::Accounts<Pubkey>,
+    params: set_split_destinations::Params,
+) -> Instruction {
+    accounts.get_instruction_cast(
+        crate::ID,
+        ProgramInstruction::SetSplitDestinations as u8,
+        params,
+    )
+}
+#[allow(missing_docs)]
+pub fn approve_milestone(
+    accounts: approve_milestone::Accounts<Pubkey>,
+    params: approve_milestone::Params,
+) -> Instruction {
+    accounts.get_instruction_cast(
+        crate::ID,
+        ProgramInstruction::ApproveMilestone as u8,
+        params,
+    )
+}
+#[allow(missing_docs)]
+pub fn freeze(accounts: freeze::Accounts<Pubkey>, params: freeze::Params) -> Instruction {
+    accounts.get_instruction_cast(crate::ID, ProgramInstruction::Freeze as u8, params)
+}
+#[allow(missing_docs)]
+pub fn thaw(accounts: thaw::Accounts<Pubkey>, params: thaw::Params) -> Instruction {
+    accounts.get_instruction_cast(crate::ID, ProgramInstruction::Thaw as u8, params)
+}
@@ -1,9 +1,13 @@
 use bonfida_utils::declare_id_with_central_state;
 
+/// Helpers to invoke the program through CPI, usable with the `no-entrypoint` feature
+pub mod cpi;
 #[doc(hidden)]
 pub mod entrypoint;
 #[doc(hidden)]
 pub mod error;
+/// Structured events logged by the program and their decoder
+pub mod events;
 /// Program instructions and their CPI-compatible bindings
 pub mod instruction;
 /// Describes the different data structres that the program uses to encode state
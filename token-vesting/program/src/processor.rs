@@ -1,17 +1,40 @@
 use {
     num_traits::FromPrimitive,
     solana_program::{
-        account_info::AccountInfo, entrypoint::ProgramResult, msg, program_error::ProgramError,
+        account_info::{next_account_info, AccountInfo},
+        entrypoint::ProgramResult,
+        msg,
+        program_error::ProgramError,
         pubkey::Pubkey,
     },
 };
 
-use bonfida_utils::WrappedPod;
+use bonfida_utils::checks::check_signer;
 
-use crate::instruction::ProgramInstruction;
+use crate::{
+    error::TokenVestingError,
+    instruction::ProgramInstruction,
+    state::{
+        mint_stats::MintStats,
+        vesting_contract::{Authority, VestingContract},
+        Tag,
+    },
+};
 
+pub mod accelerate;
+pub mod activate;
+pub mod approve_milestone;
 pub mod claim;
+pub mod claim_to_associated;
 pub mod create;
+pub mod freeze;
+pub mod get_claimable;
+pub mod migrate;
+pub mod reclaim;
+pub mod set_metadata;
+pub mod set_split_destinations;
+pub mod sweep_excess;
+pub mod thaw;
 
 pub struct Processor {}
 
@@ -22,24 +45,166 @@ impl Processor {
         instruction_data: &[u8],
     ) -> ProgramResult {
         msg!("Beginning processing");
-        let instruction = FromPrimitive::from_u8(instruction_data[0])
-            .ok_or(ProgramError::InvalidInstructionData)?;
-        let instruction_data = &instruction_data[8..];
+        if instruction_data.len() < 8 {
+            return Err(TokenVestingError::InvalidInstructionDataLength.into());
+        }
+        let (tag, instruction_data) = instruction_data.split_at(8);
+        // The tag is a little-endian u64, so only its first byte may be set
+        if tag[1..].iter().any(|b| *b != 0) {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+        let instruction =
+            FromPrimitive::from_u8(tag[0]).ok_or(ProgramError::InvalidInstructionData)?;
         msg!("Instruction unpacked");
 
         match instruction {
             ProgramInstruction::Create => {
                 msg!("Instruction: Create");
-                let params = create::Params::from_bytes(instruction_data);
+                let params = create::Params::decode(instruction_data)?;
                 create::process(program_id, accounts, params)?;
             }
             ProgramInstruction::Claim => {
                 msg!("Instruction: Claim");
-                let params = bytemuck::from_bytes(instruction_data);
+                let params = decode_params(instruction_data)?;
                 claim::process(program_id, accounts, params)?;
             }
+            ProgramInstruction::Accelerate => {
+                msg!("Instruction: Accelerate");
+                let params = decode_params(instruction_data)?;
+                accelerate::process(program_id, accounts, params)?;
+            }
+            ProgramInstruction::GetClaimable => {
+                msg!("Instruction: GetClaimable");
+                let params = decode_params(instruction_data)?;
+                get_claimable::process(program_id, accounts, params)?;
+            }
+            ProgramInstruction::Migrate => {
+                msg!("Instruction: Migrate");
+                let params = decode_params(instruction_data)?;
+                migrate::process(program_id, accounts, params)?;
+            }
+            ProgramInstruction::SetMetadata => {
+                msg!("Instruction: SetMetadata");
+                let params = decode_params(instruction_data)?;
+                set_metadata::process(program_id, accounts, params)?;
+            }
+            ProgramInstruction::Activate => {
+                msg!("Instruction: Activate");
+                let params = decode_params(instruction_data)?;
+                activate::process(program_id, accounts, params)?;
+            }
+            ProgramInstruction::SweepExcess => {
+                msg!("Instruction: SweepExcess");
+                let params = decode_params(instruction_data)?;
+                sweep_excess::process(program_id, accounts, params)?;
+            }
+            ProgramInstruction::ClaimToAssociated => {
+                msg!("Instruction: ClaimToAssociated");
+                let params = decode_params(instruction_data)?;
+                claim_to_associated::process(program_id, accounts, params)?;
+            }
+            ProgramInstruction::Reclaim => {
+                msg!("Instruction: Reclaim");
+                let params = decode_params(instruction_data)?;
+                reclaim::process(program_id, accounts, params)?;
+            }
+            ProgramInstruction::SetSplitDestinations => {
+                msg!("Instruction: SetSplitDestinations");
+                let params = decode_params(instruction_data)?;
+                set_split_destinations::process(program_id, accounts, params)?;
+            }
+            ProgramInstruction::ApproveMilestone => {
+                msg!("Instruction: ApproveMilestone");
+                let params = decode_params(instruction_data)?;
+                approve_milestone::process(program_id, accounts, params)?;
+            }
+            ProgramInstruction::Freeze => {
+                msg!("Instruction: Freeze");
+                let params = decode_params(instruction_data)?;
+                freeze::process(program_id, accounts, params)?;
+            }
+            ProgramInstruction::Thaw => {
+                msg!("Instruction: Thaw");
+                let params = decode_params(instruction_data)?;
+                thaw::process(program_id, accounts, params)?;
+            }
         }
 
         Ok(())
     }
 }
+
+/// Cast the instruction data as fixed size parameters, rejecting truncated, misaligned or trailing data
+fn decode_params<T: bytemuck::Pod>(instruction_data: &[u8]) -> Result<&T, ProgramError> {
+    bytemuck::try_from_bytes(instruction_data).map_err(|e| TokenVestingError::from(e).into())
+}
+
+/// Check that a role of a vesting contract authorized the instruction. A role held by a single key
+/// requires its signature, while a role held by an M-of-N set requires the signatures of enough
+/// listed keys among the role account and the co-signers.
+pub(crate) fn check_authority<'a>(
+    vesting_contract: &AccountInfo<'a>,
+    authority: Authority,
+    authority_account: &AccountInfo<'a>,
+    co_signers: &[AccountInfo<'a>],
+) -> ProgramResult {
+    let mut vesting_contract_guard = vesting_contract.data.borrow_mut();
+    let vesting_contract =
+        VestingContract::from_buffer(&mut vesting_contract_guard, Tag::VestingContract)?;
+
+    let signer_set = match vesting_contract.signer_set(authority) {
+        Some(signer_set) => signer_set,
+        None => return check_signer(authority_account),
+    };
+    let signers = std::iter::once(authority_account)
+        .chain(co_signers)
+        .filter(|a| a.is_signer)
+        .map(|a| a.key);
+    if signer_set.count_signatures(signers) < signer_set.threshold {
+        return Err(TokenVestingError::NotEnoughSignatures.into());
+    }
+    Ok(())
+}
+
+/// Take the statistics account of the mint of a vesting contract off the accounts, which are only
+/// passed for contracts counted in the statistics. Legacy and migrated contracts aren't, so that
+/// their existing clients keep working.
+pub(crate) fn next_mint_stats_account<'a, 'b>(
+    vesting_contract: &AccountInfo<'b>,
+    accounts_iter: &mut std::slice::Iter<'a, AccountInfo<'b>>,
+) -> Result<Option<&'a AccountInfo<'b>>, ProgramError> {
+    let mut vesting_contract_guard = vesting_contract.data.borrow_mut();
+    let vesting_contract =
+        VestingContract::from_buffer(&mut vesting_contract_guard, Tag::VestingContract)?;
+    if !vesting_contract.is_counted() {
+        return Ok(None);
+    }
+    next_account_info(accounts_iter).map(Some)
+}
+
+/// Apply `update` to the statistics of the mint of a vesting contract, which is a no-op for
+/// contracts which aren't counted in them
+pub(crate) fn update_mint_stats(
+    program_id: &Pubkey,
+    vesting_contract: &VestingContract,
+    mint_stats: Option<&AccountInfo>,
+    update: impl FnOnce(&mut MintStats) -> Result<(), TokenVestingError>,
+) -> ProgramResult {
+    let mint = match &vesting_contract.extension {
+        Some(e) if e.is_counted != 0 => e.mint,
+        _ => return Ok(()),
+    };
+    let mint_stats = mint_stats.ok_or(TokenVestingError::WrongMintStats)?;
+    // Only the PDA of a mint is ever initialized as its statistics account
+    if mint_stats.owner != program_id {
+        return Err(TokenVestingError::WrongMintStats.into());
+    }
+    let mut mint_stats_guard = mint_stats.data.borrow_mut();
+    let mint_stats = MintStats::from_buffer(&mut mint_stats_guard)
+        .map_err(|_| TokenVestingError::WrongMintStats)?;
+    if mint_stats.mint != mint {
+        return Err(TokenVestingError::WrongMintStats.into());
+    }
+    update(mint_stats)?;
+    Ok(())
+}
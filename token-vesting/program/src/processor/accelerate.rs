@@ -0,0 +1,155 @@
+//! Move the remaining unlocks of a vesting contract earlier
+
+use bonfida_utils::checks::check_account_owner;
+use bytemuck::{Pod, Zeroable};
+use solana_program::{clock::Clock, sysvar::Sysvar};
+
+use crate::{
+    error::TokenVestingError,
+    events::{AccelerateEvent, Event},
+    processor::check_authority,
+    state::{
+        self,
+        vesting_contract::{Authority, VestingContract},
+    },
+};
+
+use {
+    bonfida_utils::InstructionsAccount,
+    solana_program::{
+        account_info::{next_account_info, AccountInfo},
+        entrypoint::ProgramResult,
+        program_error::ProgramError,
+        pubkey::Pubkey,
+    },
+};
+
+/// The fraction value which designates all remaining schedules
+pub const ALL_REMAINING_SCHEDULES_BPS: u64 = 10_000;
+
+#[derive(Clone, Copy, Zeroable, Pod)]
+#[repr(C)]
+pub struct Params {
+    /// The new unlock timestamp of the accelerated schedules
+    pub unlock_timestamp: u64,
+    /// The fraction of the remaining schedules to accelerate, in basis points.
+    /// The earliest remaining schedules are accelerated first.
+    pub fraction_bps: u64,
+}
+
+#[derive(InstructionsAccount)]
+pub struct Accounts<'a, T> {
+    /// The account which stores the [`VestingContract`] data structure
+    #[cons(writable)]
+    pub vesting_contract: &'a T,
+
+    /// The grantor of the current vesting contract
+    #[cons(signer)]
+    pub grantor: &'a T,
+
+    /// The keys of the signer set of the grantor which sign the instruction, when the grantor is an
+    /// M-of-N set
+    #[cons(signer)]
+    pub co_signers: &'a [T],
+}
+
+impl<'a, 'b: 'a> Accounts<'a, AccountInfo<'b>> {
+    pub fn parse(
+        accounts: &'a [AccountInfo<'b>],
+        program_id: &Pubkey,
+    ) -> Result<Self, ProgramError> {
+        let accounts_iter = &mut accounts.iter();
+        let accounts = Accounts {
+            vesting_contract: next_account_info(accounts_iter)?,
+            grantor: next_account_info(accounts_iter)?,
+            co_signers: accounts_iter.as_slice(),
+        };
+
+        // Check owners
+        check_account_owner(accounts.vesting_contract, program_id)?;
+
+        // Check signer
+        check_authority(
+            accounts.vesting_contract,
+            Authority::Grantor,
+            accounts.grantor,
+            accounts.co_signers,
+        )?;
+
+        Ok(accounts)
+    }
+}
+
+pub fn process(program_id: &Pubkey, accounts: &[AccountInfo], params: &Params) -> ProgramResult {
+    let accounts = Accounts::parse(accounts, program_id)?;
+
+    let Params {
+        unlock_timestamp,
+        fraction_bps,
+    } = *params;
+
+    if fraction_bps == 0 || fraction_bps > ALL_REMAINING_SCHEDULES_BPS {
+        return Err(TokenVestingError::InvalidAccelerationFraction.into());
+    }
+
+    let mut vesting_contract_guard = accounts.vesting_contract.data.borrow_mut();
+    let mut vesting_contract =
+        VestingContract::from_buffer(&mut vesting_contract_guard, state::Tag::VestingContract)?;
+
+    // Contracts created without an extension have no recorded grantor
+    let grantor = vesting_contract.extension.as_ref().map(|e| &e.grantor);
+    if grantor != Some(accounts.grantor.key) {
+        return Err(TokenVestingError::WrongGrantor.into());
+    }
+
+    // Deferred contracts store their unlocks as offsets from the activation
+    if !vesting_contract.is_activated() {
+        return Err(TokenVestingError::NotActivated.into());
+    }
+    let schedule_unlock_timestamp = vesting_contract
+        .schedule_time(unlock_timestamp)
+        .ok_or(TokenVestingError::InvalidAccelerationTimestamp)?;
+
+    let current_schedule_index = vesting_contract.first_unclaimed_index();
+    let remaining_schedules = &mut vesting_contract.schedules[current_schedule_index..];
+
+    // The number of schedules to accelerate is rounded down
+    let number_of_schedules = (remaining_schedules.len() as u128)
+        .checked_mul(fraction_bps as u128)
+        .and_then(|n| n.checked_div(ALL_REMAINING_SCHEDULES_BPS as u128))
+        .ok_or(TokenVestingError::Overflow)? as usize;
+
+    if number_of_schedules == 0 {
+        return Err(TokenVestingError::NothingToAccelerate.into());
+    }
+
+    for s in remaining_schedules[..number_of_schedules].iter_mut() {
+        // An acceleration should never push back an unlock
+        if s.unlock_timestamp < schedule_unlock_timestamp {
+            return Err(TokenVestingError::AccelerationDelaysUnlock.into());
+        }
+        s.unlock_timestamp = schedule_unlock_timestamp;
+    }
+
+    // We keep a trace of the acceleration in the extension
+    let timestamp = Clock::get()?.unix_timestamp;
+    if let Some(extension) = vesting_contract.extension {
+        extension.last_acceleration_timestamp = timestamp as u64;
+        extension.acceleration_count = extension
+            .acceleration_count
+            .checked_add(1)
+            .ok_or(TokenVestingError::Overflow)?;
+    }
+
+    Event::Accelerate(AccelerateEvent {
+        vesting_contract: *accounts.vesting_contract.key,
+        grantor: *accounts.grantor.key,
+        unlock_timestamp,
+        schedule_start_index: current_schedule_index as u64,
+        schedule_end_index: (current_schedule_index + number_of_schedules) as u64,
+        timestamp,
+    })
+    .emit();
+
+    Ok(())
+}
@@ -0,0 +1,116 @@
+//! Set the activation timestamp of a contract with a deferred activation
+
+use bonfida_utils::checks::{check_account_owner, check_signer};
+use bytemuck::{Pod, Zeroable};
+use solana_program::{clock::Clock, sysvar::Sysvar};
+
+use crate::{
+    error::TokenVestingError,
+    events::{ActivateEvent, Event},
+    state::{self, vesting_contract::VestingContract},
+};
+
+use {
+    bonfida_utils::InstructionsAccount,
+    solana_program::{
+        account_info::{next_account_info, AccountInfo},
+        entrypoint::ProgramResult,
+        program_error::ProgramError,
+        pubkey::Pubkey,
+    },
+};
+
+#[derive(Clone, Copy, Zeroable, Pod)]
+#[repr(C)]
+pub struct Params {
+    /// The UTC timestamp the unlock offsets of the schedules are relative to
+    pub activation_timestamp: u64,
+}
+
+#[derive(InstructionsAccount)]
+pub struct Accounts<'a, T> {
+    /// The account which stores the [`VestingContract`] data structure
+    #[cons(writable)]
+    pub vesting_contract: &'a T,
+
+    /// The activator of the current vesting contract
+    #[cons(signer)]
+    pub activator: &'a T,
+}
+
+impl<'a, 'b: 'a> Accounts<'a, AccountInfo<'b>> {
+    pub fn parse(
+        accounts: &'a [AccountInfo<'b>],
+        program_id: &Pubkey,
+    ) -> Result<Self, ProgramError> {
+        let accounts_iter = &mut accounts.iter();
+        let accounts = Accounts {
+            vesting_contract: next_account_info(accounts_iter)?,
+            activator: next_account_info(accounts_iter)?,
+        };
+
+        // Check owners
+        check_account_owner(accounts.vesting_contract, program_id)?;
+
+        // Check signer
+        check_signer(accounts.activator)?;
+
+        Ok(accounts)
+    }
+}
+
+pub fn process(program_id: &Pubkey, accounts: &[AccountInfo], params: &Params) -> ProgramResult {
+    let accounts = Accounts::parse(accounts, program_id)?;
+
+    let Params {
+        activation_timestamp,
+    } = *params;
+
+    let mut vesting_contract_guard = accounts.vesting_contract.data.borrow_mut();
+    let mut vesting_contract =
+        VestingContract::from_buffer(&mut vesting_contract_guard, state::Tag::VestingContract)?;
+
+    // Contracts without a deferred activation have no activator
+    if !vesting_contract.has_deferred_activation() {
+        return Err(TokenVestingError::WrongActivator.into());
+    }
+
+    let last_offset = vesting_contract
+        .schedules
+        .last()
+        .map(|s| s.unlock_timestamp)
+        .unwrap_or(0);
+    let extension = vesting_contract.extension_mut()?;
+
+    if &extension.activator != accounts.activator.key {
+        return Err(TokenVestingError::WrongActivator.into());
+    }
+
+    // The activation can only happen once
+    if extension.activation_timestamp != 0 {
+        return Err(TokenVestingError::AlreadyActivated.into());
+    }
+
+    // Zero designates an unactivated contract, and the schedules are sorted
+    let last_unlock = match activation_timestamp.checked_add(last_offset) {
+        Some(t) if activation_timestamp != 0 => t,
+        _ => return Err(TokenVestingError::InvalidActivationTimestamp.into()),
+    };
+
+    // The expiry must leave time to claim every unlock, which isn't checked at creation
+    if extension.expiry_timestamp != 0 && last_unlock >= extension.expiry_timestamp {
+        return Err(TokenVestingError::InvalidExpiry.into());
+    }
+
+    extension.activation_timestamp = activation_timestamp;
+
+    Event::Activate(ActivateEvent {
+        vesting_contract: *accounts.vesting_contract.key,
+        activator: *accounts.activator.key,
+        activation_timestamp,
+        timestamp: Clock::get()?.unix_timestamp,
+    })
+    .emit();
+
+    Ok(())
+}
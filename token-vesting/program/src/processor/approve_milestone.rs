@@ -0,0 +1,93 @@
+//! Approve the milestone gating an entry of the schedule of a vesting contract
+
+use std::convert::TryFrom;
+
+use bonfida_utils::checks::{check_account_owner, check_signer};
+use bytemuck::{Pod, Zeroable};
+use solana_program::{clock::Clock, sysvar::Sysvar};
+
+use crate::{
+    error::TokenVestingError,
+    events::{Event, MilestoneEvent},
+    state::{self, vesting_contract::VestingContract},
+};
+
+use {
+    bonfida_utils::InstructionsAccount,
+    solana_program::{
+        account_info::{next_account_info, AccountInfo},
+        entrypoint::ProgramResult,
+        program_error::ProgramError,
+        pubkey::Pubkey,
+    },
+};
+
+#[derive(Clone, Copy, Zeroable, Pod)]
+#[repr(C)]
+pub struct Params {
+    /// The index in the schedule vector of the entry whose milestone is reached
+    pub schedule_index: u64,
+}
+
+#[derive(InstructionsAccount)]
+pub struct Accounts<'a, T> {
+    /// The account which stores the [`VestingContract`] data structure
+    #[cons(writable)]
+    pub vesting_contract: &'a T,
+
+    /// The milestone approver of the current vesting contract
+    #[cons(signer)]
+    pub approver: &'a T,
+}
+
+impl<'a, 'b: 'a> Accounts<'a, AccountInfo<'b>> {
+    pub fn parse(
+        accounts: &'a [AccountInfo<'b>],
+        program_id: &Pubkey,
+    ) -> Result<Self, ProgramError> {
+        let accounts_iter = &mut accounts.iter();
+        let accounts = Accounts {
+            vesting_contract: next_account_info(accounts_iter)?,
+            approver: next_account_info(accounts_iter)?,
+        };
+
+        // Check owners
+        check_account_owner(accounts.vesting_contract, program_id)?;
+
+        // Check signer
+        check_signer(accounts.approver)?;
+
+        Ok(accounts)
+    }
+}
+
+pub fn process(program_id: &Pubkey, accounts: &[AccountInfo], params: &Params) -> ProgramResult {
+    let accounts = Accounts::parse(accounts, program_id)?;
+
+    let Params { schedule_index } = params;
+
+    let mut vesting_contract_guard = accounts.vesting_contract.data.borrow_mut();
+    let mut vesting_contract =
+        VestingContract::from_buffer(&mut vesting_contract_guard, state::Tag::VestingContract)?;
+
+    let extension = vesting_contract.extension_mut()?;
+    if extension.milestone_approver == Pubkey::default()
+        || &extension.milestone_approver != accounts.approver.key
+    {
+        return Err(TokenVestingError::WrongMilestoneApprover.into());
+    }
+
+    // Out of range indices are never gated
+    let index = usize::try_from(*schedule_index).unwrap_or(usize::MAX);
+    extension.approve_milestone(index)?;
+
+    Event::Milestone(MilestoneEvent {
+        vesting_contract: *accounts.vesting_contract.key,
+        approver: *accounts.approver.key,
+        schedule_index: *schedule_index,
+        timestamp: Clock::get()?.unix_timestamp,
+    })
+    .emit();
+
+    Ok(())
+}
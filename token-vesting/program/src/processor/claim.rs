@@ -1,10 +1,18 @@
 //! Claim unvested tokens
 
-use bonfida_utils::checks::{check_account_key, check_account_owner, check_signer};
+use bonfida_utils::checks::{check_account_key, check_account_owner};
 use bytemuck::{Pod, Zeroable};
-use solana_program::{clock::Clock, msg, program::invoke_signed, sysvar::Sysvar};
+use solana_program::{clock::Clock, program::invoke_signed, program_pack::Pack, sysvar::Sysvar};
 
-use crate::state::{self, vesting_contract::VestingContract};
+use crate::{
+    error::TokenVestingError,
+    events::{ClaimEvent, Event},
+    processor::{check_authority, next_mint_stats_account, update_mint_stats},
+    state::{
+        self,
+        vesting_contract::{Authority, SplitDestination, VestingContract},
+    },
+};
 
 use {
     bonfida_utils::InstructionsAccount,
@@ -43,6 +51,22 @@ pub struct Accounts<'a, T> {
     /// The owner of the current vesting contract
     #[cons(signer)]
     pub owner: &'a T,
+
+    /// The [`MintStats`](crate::state::mint_stats::MintStats) PDA of the mint of the contract,
+    /// only passed for contracts counted in the statistics
+    #[cons(writable)]
+    pub mint_stats: Option<&'a T>,
+
+    /// The withholding token account when the grantor set one, followed by the token accounts of
+    /// the split destinations registered by the owner but the first one, which is the destination
+    /// token account, in registration order
+    #[cons(writable)]
+    pub extra_destination_token_accounts: &'a [T],
+
+    /// The keys of the signer set of the owner which sign the instruction, when the owner is an
+    /// M-of-N set
+    #[cons(signer)]
+    pub co_signers: &'a [T],
 }
 
 impl<'a, 'b: 'a> Accounts<'a, AccountInfo<'b>> {
@@ -51,13 +75,16 @@ impl<'a, 'b: 'a> Accounts<'a, AccountInfo<'b>> {
         program_id: &Pubkey,
     ) -> Result<Self, ProgramError> {
         let accounts_iter = &mut accounts.iter();
-        let accounts = Accounts {
+        let mut accounts = Accounts {
             spl_token_program: next_account_info(accounts_iter)?,
             vesting_contract: next_account_info(accounts_iter)?,
             vesting_contract_signer: next_account_info(accounts_iter)?,
             vault: next_account_info(accounts_iter)?,
             destination_token_account: next_account_info(accounts_iter)?,
             owner: next_account_info(accounts_iter)?,
+            mint_stats: None,
+            extra_destination_token_accounts: &[],
+            co_signers: &[],
         };
 
         // Check keys
@@ -68,8 +95,19 @@ impl<'a, 'b: 'a> Accounts<'a, AccountInfo<'b>> {
         check_account_owner(accounts.vault, &spl_token::ID)?;
         check_account_owner(accounts.destination_token_account, &spl_token::ID)?;
 
+        accounts.mint_stats = next_mint_stats_account(accounts.vesting_contract, accounts_iter)?;
+        let (extra_destination_token_accounts, co_signers) =
+            split_trailing_accounts(accounts.vesting_contract, accounts_iter.as_slice())?;
+        accounts.extra_destination_token_accounts = extra_destination_token_accounts;
+        accounts.co_signers = co_signers;
+
         // Check signer
-        check_signer(accounts.owner)?;
+        check_authority(
+            accounts.vesting_contract,
+            Authority::Owner,
+            accounts.owner,
+            accounts.co_signers,
+        )?;
 
         Ok(accounts)
     }
@@ -78,15 +116,25 @@ impl<'a, 'b: 'a> Accounts<'a, AccountInfo<'b>> {
 pub fn process(program_id: &Pubkey, accounts: &[AccountInfo], _params: &Params) -> ProgramResult {
     let accounts = Accounts::parse(accounts, program_id)?;
 
+    release_unlocked_tokens(program_id, &accounts)?;
+
+    Ok(())
+}
+
+/// Release the unlocked schedules of the contract and transfer them to the destination account.
+/// Returns the quantity of tokens released, including the withheld share.
+pub(crate) fn release_unlocked_tokens(
+    program_id: &Pubkey,
+    accounts: &Accounts<AccountInfo>,
+) -> Result<u64, ProgramError> {
     // We begin by parsing the vesting contract account
     let mut vesting_contract_guard = accounts.vesting_contract.data.borrow_mut();
-    let vesting_contract =
+    let mut vesting_contract =
         VestingContract::from_buffer(&mut vesting_contract_guard, state::Tag::VestingContract)?;
 
     // We check that the specified owner actually owns this contract
     if &vesting_contract.header.owner != accounts.owner.key {
-        msg!("Invalid vesting contract owner!");
-        return Err(ProgramError::InvalidArgument);
+        return Err(TokenVestingError::WrongContractOwner.into());
     }
 
     // We also check that the vault is the correct one
@@ -94,75 +142,185 @@ pub fn process(program_id: &Pubkey, accounts: &[AccountInfo], _params: &Params)
     // This isn't strictly necessary and the call to spl_token would fail.
     // This is defense in depth. Also it makes for nicer error messages.
     if &vesting_contract.header.vault != accounts.vault.key {
-        msg!("Invalid vault provided!");
-        return Err(ProgramError::InvalidArgument);
+        return Err(TokenVestingError::WrongVault.into());
     }
 
     // We derive and check that the provided contract signer is correct.
     // In the same way, this isn't strictly necessary.
     // The call to invoke_signed would fail if this wasn't the case.
+    // Contracts created before the namespaced derivation keep their original seeds
     let contract_signer_key = Pubkey::create_program_address(
-        &[
-            &accounts.vesting_contract.key.to_bytes(),
-            &[vesting_contract.header.signer_nonce as u8],
-        ],
+        &vesting_contract
+            .header
+            .signer_seeds(accounts.vesting_contract.key),
         program_id,
     )?;
 
     if &contract_signer_key != accounts.vesting_contract_signer.key {
-        msg!("Invalid contract signer provided!");
-        return Err(ProgramError::InvalidArgument);
+        return Err(TokenVestingError::WrongContractSigner.into());
     }
 
     // We get the current timestamp from the Clock sysvar
-    let current_timestamp = Clock::get()?.unix_timestamp as u64;
-
-    let mut total_amount_to_transfer: u64 = 0;
-
-    // We saturate the vesting_contract.header.current_schedule_index variable in case we don't break
-    // out of our loop. Not doing this would leave the contract empty but in a weird state
-    let current_schedule_index = vesting_contract.header.current_schedule_index as usize;
-    vesting_contract.header.current_schedule_index = u64::MAX;
-
-    for (idx, s) in vesting_contract.schedules[current_schedule_index..]
-        .iter_mut()
-        .enumerate()
-    {
-        if s.unlock_timestamp > current_timestamp {
-            // We update the current_schedule_index for the next call to claim
-            // This prevents the same quantity from being unlocked twice
-            vesting_contract.header.current_schedule_index = idx as u64;
-            break;
-        }
+    let timestamp = Clock::get()?.unix_timestamp;
+    let current_timestamp = timestamp as u64;
 
-        total_amount_to_transfer = total_amount_to_transfer.checked_add(s.quantity).unwrap();
-        // We zero out the schedule. This isn't strictly necessary as well since we
-        // update the current_schedule_index. Defense in depth.
-        s.quantity = 0;
+    if vesting_contract.is_fully_claimed() {
+        return Err(TokenVestingError::ContractFullyClaimed.into());
     }
 
-    let transfer_instruction = spl_token::instruction::transfer(
-        &spl_token::ID,
-        accounts.vault.key,
-        accounts.destination_token_account.key,
-        accounts.vesting_contract_signer.key,
-        &[],
-        total_amount_to_transfer,
-    )?;
+    // Unclaimed tokens belong to the grantor once the contract expires
+    if vesting_contract.is_expired(current_timestamp) {
+        return Err(TokenVestingError::ContractExpired.into());
+    }
+
+    if vesting_contract.is_frozen() {
+        return Err(TokenVestingError::ContractFrozen.into());
+    }
+
+    let schedule_start_index = vesting_contract.first_unclaimed_index();
+    let total_amount_to_transfer =
+        vesting_contract.release_unlocked_schedules(current_timestamp)?;
+    let schedule_end_index = vesting_contract.first_unclaimed_index();
 
-    invoke_signed(
-        &transfer_instruction,
-        &[
-            accounts.spl_token_program.clone(),
-            accounts.vault.clone(),
-            accounts.destination_token_account.clone(),
-            accounts.vesting_contract_signer.clone(),
-        ],
-        &[&[
-            &accounts.vesting_contract.key.to_bytes(),
-            &[vesting_contract.header.signer_nonce as u8],
-        ]],
+    if total_amount_to_transfer == 0 {
+        return Err(TokenVestingError::NothingToClaim.into());
+    }
+
+    if let Some(extension) = vesting_contract.extension.as_deref_mut() {
+        extension.claimed_amount = extension
+            .claimed_amount
+            .checked_add(total_amount_to_transfer)
+            .ok_or(TokenVestingError::Overflow)?;
+    }
+
+    let is_fully_claimed = vesting_contract.is_fully_claimed();
+    update_mint_stats(
+        program_id,
+        &vesting_contract,
+        accounts.mint_stats,
+        |mint_stats| mint_stats.record_claim(total_amount_to_transfer, is_fully_claimed),
     )?;
 
-    Ok(())
+    let (withheld_amount, transfers) =
+        withhold_and_split_release(&vesting_contract, accounts, total_amount_to_transfer)?;
+
+    for (destination, amount) in transfers {
+        if amount == 0 {
+            continue;
+        }
+
+        let transfer_instruction = spl_token::instruction::transfer(
+            &spl_token::ID,
+            accounts.vault.key,
+            destination.key,
+            accounts.vesting_contract_signer.key,
+            &[],
+            amount,
+        )?;
+
+        invoke_signed(
+            &transfer_instruction,
+            &[
+                accounts.spl_token_program.clone(),
+                accounts.vault.clone(),
+                destination.clone(),
+                accounts.vesting_contract_signer.clone(),
+            ],
+            &[&vesting_contract
+                .header
+                .signer_seeds(accounts.vesting_contract.key)],
+        )?;
+    }
+
+    let vault_account = spl_token::state::Account::unpack(&accounts.vault.data.borrow())?;
+
+    Event::Claim(ClaimEvent {
+        vesting_contract: *accounts.vesting_contract.key,
+        owner: *accounts.owner.key,
+        mint: vault_account.mint,
+        destination: *accounts.destination_token_account.key,
+        amount: total_amount_to_transfer - withheld_amount,
+        withheld_amount,
+        schedule_start_index: schedule_start_index as u64,
+        schedule_end_index: schedule_end_index as u64,
+        timestamp,
+    })
+    .emit();
+
+    Ok(total_amount_to_transfer)
+}
+
+/// Split the trailing accounts of a claim between the extra destination token accounts and the
+/// co-signers of the owner which follow them, when the owner is an M-of-N set
+pub(crate) fn split_trailing_accounts<'a, 'b>(
+    vesting_contract: &AccountInfo<'b>,
+    trailing_accounts: &'a [AccountInfo<'b>],
+) -> Result<(&'a [AccountInfo<'b>], &'a [AccountInfo<'b>]), ProgramError> {
+    let mut vesting_contract_guard = vesting_contract.data.borrow_mut();
+    let vesting_contract =
+        VestingContract::from_buffer(&mut vesting_contract_guard, state::Tag::VestingContract)?;
+    if vesting_contract.signer_set(Authority::Owner).is_none() {
+        return Ok((trailing_accounts, &[]));
+    }
+    let extra_destination_count = std::cmp::min(
+        vesting_contract.extra_destination_count(),
+        trailing_accounts.len(),
+    );
+    Ok(trailing_accounts.split_at(extra_destination_count))
+}
+
+/// The token accounts receiving a release along with their share
+type Transfers<'a, 'b> = Vec<(&'a AccountInfo<'b>, u64)>;
+
+/// Transfer the withheld share of a release to the withholding token account, and divide the rest
+/// between the destinations registered by the owner, or send all of it to the destination token
+/// account when releases aren't divided. Returns the withheld quantity along with the transfers.
+fn withhold_and_split_release<'a, 'b>(
+    vesting_contract: &VestingContract,
+    accounts: &Accounts<'a, AccountInfo<'b>>,
+    amount: u64,
+) -> Result<(u64, Transfers<'a, 'b>), ProgramError> {
+    let mut extra_accounts = accounts.extra_destination_token_accounts;
+    let mut transfers = vec![];
+
+    let (withheld_amount, amount) = vesting_contract.withhold(amount);
+    if let Some(withholding_account) = vesting_contract.withholding_account() {
+        let (withholding_token_account, remaining) = extra_accounts
+            .split_first()
+            .ok_or(TokenVestingError::WrongWithholdingAccount)?;
+        if withholding_token_account.key != withholding_account {
+            return Err(TokenVestingError::WrongWithholdingAccount.into());
+        }
+        transfers.push((withholding_token_account, withheld_amount));
+        extra_accounts = remaining;
+    }
+
+    let split_destinations = vesting_contract.split_destinations();
+    let destination_accounts = std::iter::once(accounts.destination_token_account)
+        .chain(extra_accounts.iter())
+        .collect::<Vec<_>>();
+
+    if split_destinations.is_empty() {
+        if destination_accounts.len() != 1 {
+            return Err(TokenVestingError::WrongSplitDestinations.into());
+        }
+        transfers.push((accounts.destination_token_account, amount));
+        return Ok((withheld_amount, transfers));
+    }
+
+    let is_valid = destination_accounts.len() == split_destinations.len()
+        && destination_accounts
+            .iter()
+            .zip(split_destinations)
+            .all(|(a, d)| a.key == &d.token_account);
+    if !is_valid {
+        return Err(TokenVestingError::WrongSplitDestinations.into());
+    }
+
+    transfers.extend(
+        destination_accounts
+            .into_iter()
+            .zip(SplitDestination::split_amount(split_destinations, amount)),
+    );
+    Ok((withheld_amount, transfers))
 }
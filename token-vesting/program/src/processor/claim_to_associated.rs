@@ -0,0 +1,249 @@
+//! Claim unvested tokens to the associated token account of the owner, creating it if needed
+
+use bonfida_utils::checks::{check_account_key, check_account_owner, check_signer};
+use bytemuck::{Pod, Zeroable};
+use enumflags2::{bitflags, BitFlags};
+use solana_program::{program::invoke, program_pack::Pack, system_program};
+use spl_associated_token_account::{
+    get_associated_token_address, instruction::create_associated_token_account,
+};
+
+use crate::{
+    error::TokenVestingError,
+    processor::{check_authority, claim, next_mint_stats_account},
+    state::{
+        self,
+        vesting_contract::{Authority, VestingContract},
+    },
+};
+
+use {
+    bonfida_utils::InstructionsAccount,
+    solana_program::{
+        account_info::{next_account_info, AccountInfo},
+        entrypoint::ProgramResult,
+        program_error::ProgramError,
+        pubkey::Pubkey,
+    },
+};
+
+#[derive(Clone, Copy, Zeroable, Pod)]
+#[repr(C)]
+pub struct Params {
+    /// A combination of [`ClaimFlag`] values
+    pub flags: u64,
+}
+
+#[bitflags]
+#[repr(u64)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+/// Options of the claim to an associated token account
+pub enum ClaimFlag {
+    /// Close the associated token account once the wrapped SOL is claimed, which transfers its
+    /// whole balance to the owner as lamports. The account must not hold any token before the
+    /// claim, and the owner must be a single key.
+    UnwrapNative = 1,
+}
+
+#[derive(InstructionsAccount)]
+pub struct Accounts<'a, T> {
+    /// SPL token program account
+    pub spl_token_program: &'a T,
+
+    /// The account which stores the vesting contract data structure
+    #[cons(writable)]
+    pub vesting_contract: &'a T,
+
+    /// The signing PDA which owns the vault
+    pub vesting_contract_signer: &'a T,
+
+    /// The contract's escrow vault
+    #[cons(writable)]
+    pub vault: &'a T,
+
+    /// The associated token account of the owner, created if it doesn't exist
+    #[cons(writable)]
+    pub destination_token_account: &'a T,
+
+    /// The owner of the current vesting contract, which receives the unwrapped lamports
+    #[cons(writable, signer)]
+    pub owner: &'a T,
+
+    /// The mint of the vested tokens
+    pub mint: &'a T,
+
+    /// The system program account
+    pub system_program: &'a T,
+
+    /// The associated token account program account
+    pub associated_token_program: &'a T,
+
+    /// The account paying for the creation of the associated token account
+    #[cons(writable, signer)]
+    pub payer: &'a T,
+
+    /// The [`MintStats`](crate::state::mint_stats::MintStats) PDA of the mint of the contract,
+    /// only passed for contracts counted in the statistics
+    #[cons(writable)]
+    pub mint_stats: Option<&'a T>,
+
+    /// The withholding token account and the token accounts of the split destinations, as in
+    /// [`claim::Accounts`]
+    #[cons(writable)]
+    pub extra_destination_token_accounts: &'a [T],
+
+    /// The keys of the signer set of the owner which sign the instruction, when the owner is an
+    /// M-of-N set
+    #[cons(signer)]
+    pub co_signers: &'a [T],
+}
+
+impl<'a, 'b: 'a> Accounts<'a, AccountInfo<'b>> {
+    pub fn parse(
+        accounts: &'a [AccountInfo<'b>],
+        program_id: &Pubkey,
+    ) -> Result<Self, ProgramError> {
+        let accounts_iter = &mut accounts.iter();
+        let mut accounts = Accounts {
+            spl_token_program: next_account_info(accounts_iter)?,
+            vesting_contract: next_account_info(accounts_iter)?,
+            vesting_contract_signer: next_account_info(accounts_iter)?,
+            vault: next_account_info(accounts_iter)?,
+            destination_token_account: next_account_info(accounts_iter)?,
+            owner: next_account_info(accounts_iter)?,
+            mint: next_account_info(accounts_iter)?,
+            system_program: next_account_info(accounts_iter)?,
+            associated_token_program: next_account_info(accounts_iter)?,
+            payer: next_account_info(accounts_iter)?,
+            mint_stats: None,
+            extra_destination_token_accounts: &[],
+            co_signers: &[],
+        };
+
+        // Check keys
+        check_account_key(accounts.spl_token_program, &spl_token::ID)?;
+        check_account_key(accounts.system_program, &system_program::ID)?;
+        check_account_key(
+            accounts.associated_token_program,
+            &spl_associated_token_account::ID,
+        )?;
+
+        // Check owners
+        check_account_owner(accounts.vesting_contract, program_id)?;
+        check_account_owner(accounts.vault, &spl_token::ID)?;
+        check_account_owner(accounts.mint, &spl_token::ID)?;
+
+        accounts.mint_stats = next_mint_stats_account(accounts.vesting_contract, accounts_iter)?;
+        let (extra_destination_token_accounts, co_signers) =
+            claim::split_trailing_accounts(accounts.vesting_contract, accounts_iter.as_slice())?;
+        accounts.extra_destination_token_accounts = extra_destination_token_accounts;
+        accounts.co_signers = co_signers;
+
+        // Check signer
+        check_authority(
+            accounts.vesting_contract,
+            Authority::Owner,
+            accounts.owner,
+            accounts.co_signers,
+        )?;
+        check_signer(accounts.payer)?;
+
+        Ok(accounts)
+    }
+}
+
+pub fn process(program_id: &Pubkey, accounts: &[AccountInfo], params: &Params) -> ProgramResult {
+    let accounts = Accounts::parse(accounts, program_id)?;
+
+    let flags = BitFlags::<ClaimFlag>::from_bits(params.flags)
+        .map_err(|_| TokenVestingError::InvalidClaimFlags)?;
+
+    let vault_account = spl_token::state::Account::unpack(&accounts.vault.data.borrow())?;
+    if &vault_account.mint != accounts.mint.key {
+        return Err(TokenVestingError::MintMismatch.into());
+    }
+    if flags.contains(ClaimFlag::UnwrapNative) {
+        if !vault_account.is_native() {
+            return Err(TokenVestingError::NotNativeMint.into());
+        }
+        // Closing the account requires the signature of the owner key itself
+        let mut vesting_contract_guard = accounts.vesting_contract.data.borrow_mut();
+        let vesting_contract =
+            VestingContract::from_buffer(&mut vesting_contract_guard, state::Tag::VestingContract)?;
+        if vesting_contract.signer_set(Authority::Owner).is_some() {
+            return Err(TokenVestingError::SignerSetCannotUnwrap.into());
+        }
+    }
+
+    let associated_token_account =
+        get_associated_token_address(accounts.owner.key, accounts.mint.key);
+    if &associated_token_account != accounts.destination_token_account.key {
+        return Err(TokenVestingError::InvalidAssociatedTokenAccount.into());
+    }
+
+    // The associated token account is only created when it doesn't exist yet
+    if accounts.destination_token_account.data_is_empty() {
+        let instruction = create_associated_token_account(
+            accounts.payer.key,
+            accounts.owner.key,
+            accounts.mint.key,
+        );
+        invoke(
+            &instruction,
+            &[
+                accounts.associated_token_program.clone(),
+                accounts.payer.clone(),
+                accounts.destination_token_account.clone(),
+                accounts.owner.clone(),
+                accounts.mint.clone(),
+                accounts.system_program.clone(),
+                accounts.spl_token_program.clone(),
+            ],
+        )?;
+    }
+    check_account_owner(accounts.destination_token_account, &spl_token::ID)?;
+
+    // Closing the account unwraps its whole balance, which should only be the claimed tokens
+    if flags.contains(ClaimFlag::UnwrapNative) {
+        let destination_account =
+            spl_token::state::Account::unpack(&accounts.destination_token_account.data.borrow())?;
+        if destination_account.amount != 0 {
+            return Err(TokenVestingError::NativeAccountNotEmpty.into());
+        }
+    }
+
+    claim::release_unlocked_tokens(
+        program_id,
+        &claim::Accounts {
+            spl_token_program: accounts.spl_token_program,
+            vesting_contract: accounts.vesting_contract,
+            vesting_contract_signer: accounts.vesting_contract_signer,
+            vault: accounts.vault,
+            destination_token_account: accounts.destination_token_account,
+            owner: accounts.owner,
+            mint_stats: accounts.mint_stats,
+            extra_destination_token_accounts: accounts.extra_destination_token_accounts,
+            co_signers: accounts.co_signers,
+        },
+    )?;
+
+    if flags.contains(ClaimFlag::UnwrapNative) {
+        let instruction = spl_token::instruction::close_account(
+            &spl_token::ID,
+            accounts.destination_token_account.key,
+            accounts.owner.key,
+            accounts.owner.key,
+            &[],
+        )?;
+        invoke(
+            &instruction,
+            &[
+                accounts.spl_token_program.clone(),
+                accounts.destination_token_account.clone(),
+                accounts.owner.clone(),
+            ],
+        )?;
+    }
+
+    Ok(())
+}
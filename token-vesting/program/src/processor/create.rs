@@ -1,14 +1,32 @@
 //! Create a new token vesting contract
 
+use std::convert::TryFrom;
+
 use bonfida_utils::{checks::check_account_owner, WrappedPod};
-use solana_program::{msg, program::invoke, program_pack::Pack};
+use bytemuck::Zeroable;
+use enumflags2::{bitflags, BitFlags};
+use solana_program::{
+    clock::Clock,
+    program::{invoke, invoke_signed},
+    program_pack::Pack,
+    rent::Rent,
+    system_instruction, system_program,
+    sysvar::Sysvar,
+};
 use spl_token::state::AccountState;
 
 use crate::{
     error::TokenVestingError,
+    events::{CreateEvent, Event, MetadataEvent},
+    processor::update_mint_stats,
     state::{
         self,
-        vesting_contract::{VestingContract, VestingContractHeader, VestingSchedule},
+        mint_stats::MintStats,
+        owner_registry::OwnerRegistry,
+        vesting_contract::{
+            ContractMetadata, MilestoneBitmap, SignerSet, SplitDestination, VestingContract,
+            VestingContractExtension, VestingContractHeader, VestingSchedule,
+        },
     },
 };
 
@@ -28,9 +46,94 @@ use {
 #[derive(WrappedPod)]
 pub struct Params<'a> {
     pub signer_nonce: &'a u64,
+    /// A combination of [`CreateFlag`] values
+    pub flags: &'a u64,
+    /// The optional label and metadata URI of the contract
+    pub metadata: &'a ContractMetadata,
+    /// The account allowed to activate the contract, or the default key for a contract which
+    /// is active right away. When set, the unlock timestamps are offsets from the activation.
+    pub activator: &'a Pubkey,
+    /// The UTC timestamp after which the grantor can reclaim the unclaimed tokens, or zero for a
+    /// contract which never expires
+    pub expiry_timestamp: &'a u64,
+    /// The share of each release transferred to the withholding token account, in basis points.
+    /// Zero when nothing is withheld, in which case no withholding token account is passed.
+    pub withholding_bps: &'a u64,
+    /// The account allowed to approve milestones, or the default key when no entry is gated
+    pub milestone_approver: &'a Pubkey,
+    /// Bitmap of the schedule entries which are only released once their milestone is approved,
+    /// by index in the schedule. Only the first 256 entries can be gated.
+    pub milestone_gated: &'a MilestoneBitmap,
+    /// The M-of-N set holding the owner role, zeroed out when the recipient key alone holds it
+    pub owner_signers: &'a SignerSet,
+    /// The M-of-N set holding the grantor role, zeroed out when the source tokens owner alone
+    /// holds it
+    pub grantor_signers: &'a SignerSet,
+    /// The account allowed to freeze the releases of the contract, or the default key for a
+    /// contract which can't be frozen
+    pub freeze_authority: &'a Pubkey,
     pub schedule: &'a [VestingSchedule],
 }
 
+#[bitflags]
+#[repr(u64)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+/// Options relaxing the validation of a new vesting contract
+pub enum CreateFlag {
+    /// Allow schedules which are already unlocked, for back-dated grants
+    AllowPastUnlocks = 1,
+    /// List the contract in the [`OwnerRegistry`] of the recipient, which is created or grown as
+    /// needed
+    RegisterOwner = 2,
+    /// Store the cumulative quantity unlocked up to each schedule, so that claims find the
+    /// unlocked schedules by binary search. Schedules can't be gated by milestones.
+    CumulativeLayout = 4,
+}
+
+impl<'a> Params<'a> {
+    /// Decode the instruction parameters, rejecting truncated, misaligned or trailing data
+    pub fn decode(instruction_data: &'a [u8]) -> Result<Self, ProgramError> {
+        let mut instruction_data = instruction_data;
+        let signer_nonce = take(&mut instruction_data)?;
+        let flags = take(&mut instruction_data)?;
+        let metadata = take(&mut instruction_data)?;
+        let activator = take(&mut instruction_data)?;
+        let expiry_timestamp = take(&mut instruction_data)?;
+        let withholding_bps = take(&mut instruction_data)?;
+        let milestone_approver = take(&mut instruction_data)?;
+        let milestone_gated = take(&mut instruction_data)?;
+        let owner_signers = take(&mut instruction_data)?;
+        let grantor_signers = take(&mut instruction_data)?;
+        let freeze_authority = take(&mut instruction_data)?;
+        let schedule =
+            bytemuck::try_cast_slice(instruction_data).map_err(TokenVestingError::from)?;
+        Ok(Self {
+            signer_nonce,
+            flags,
+            metadata,
+            activator,
+            expiry_timestamp,
+            withholding_bps,
+            milestone_approver,
+            milestone_gated,
+            owner_signers,
+            grantor_signers,
+            freeze_authority,
+            schedule,
+        })
+    }
+}
+
+/// Split a fixed size parameter off the front of the instruction data
+fn take<'a, T: bytemuck::Pod>(instruction_data: &mut &'a [u8]) -> Result<&'a T, ProgramError> {
+    if instruction_data.len() < std::mem::size_of::<T>() {
+        return Err(TokenVestingError::InvalidInstructionDataLength.into());
+    }
+    let (value, remaining) = instruction_data.split_at(std::mem::size_of::<T>());
+    *instruction_data = remaining;
+    Ok(bytemuck::try_from_bytes(value).map_err(TokenVestingError::from)?)
+}
+
 #[derive(InstructionsAccount)]
 pub struct Accounts<'a, T> {
     /// SPL token program account
@@ -54,12 +157,33 @@ pub struct Accounts<'a, T> {
 
     /// The eventual recipient of the vested tokens
     pub recipient: &'a T,
+
+    /// The system program account
+    pub system_program: &'a T,
+
+    /// The account paying for the rent of the contract, which is refunded to it
+    #[cons(writable, signer)]
+    pub payer: &'a T,
+
+    /// The [`MintStats`] PDA of the vested mint, created if it doesn't exist yet
+    #[cons(writable)]
+    pub mint_stats: &'a T,
+
+    /// The [`OwnerRegistry`] PDA of the recipient, only with the [`CreateFlag::RegisterOwner`]
+    /// flag
+    #[cons(writable)]
+    pub owner_registry: Option<&'a T>,
+
+    /// The token account receiving the withheld share of each release, only when the withholding
+    /// rate isn't zero
+    pub withholding_token_account: Option<&'a T>,
 }
 
 impl<'a, 'b: 'a> Accounts<'a, AccountInfo<'b>> {
     pub fn parse(
         accounts: &'a [AccountInfo<'b>],
         program_id: &Pubkey,
+        flags: BitFlags<CreateFlag>,
     ) -> Result<Self, ProgramError> {
         let accounts_iter = &mut accounts.iter();
         let accounts = Accounts {
@@ -69,52 +193,116 @@ impl<'a, 'b: 'a> Accounts<'a, AccountInfo<'b>> {
             source_tokens: next_account_info(accounts_iter)?,
             source_tokens_owner: next_account_info(accounts_iter)?,
             recipient: next_account_info(accounts_iter)?,
+            system_program: next_account_info(accounts_iter)?,
+            payer: next_account_info(accounts_iter)?,
+            mint_stats: next_account_info(accounts_iter)?,
+            owner_registry: if flags.contains(CreateFlag::RegisterOwner) {
+                Some(next_account_info(accounts_iter)?)
+            } else {
+                None
+            },
+            withholding_token_account: next_account_info(accounts_iter).ok(),
         };
 
         // Check keys
         check_account_key(accounts.spl_token_program, &spl_token::ID)?;
+        check_account_key(accounts.system_program, &system_program::ID)?;
 
         // Check owners
         check_account_owner(accounts.vesting_contract, program_id)?;
         check_account_owner(accounts.vault, &spl_token::ID)?;
+        check_account_owner(accounts.source_tokens, &spl_token::ID)?;
+        if let Some(withholding_token_account) = accounts.withholding_token_account {
+            check_account_owner(withholding_token_account, &spl_token::ID)?;
+        }
 
         // Check signer
         check_signer(accounts.source_tokens_owner)?;
+        check_signer(accounts.payer)?;
 
         Ok(accounts)
     }
 }
 
 pub fn process(program_id: &Pubkey, accounts: &[AccountInfo], params: Params) -> ProgramResult {
-    let accounts = Accounts::parse(accounts, program_id)?;
+    let flags = BitFlags::<CreateFlag>::from_bits(*params.flags)
+        .map_err(|_| TokenVestingError::InvalidCreateFlags)?;
+    let accounts = Accounts::parse(accounts, program_id, flags)?;
 
     let Params {
         signer_nonce,
+        metadata,
+        activator,
+        expiry_timestamp,
+        withholding_bps,
+        milestone_approver,
+        milestone_gated,
+        owner_signers,
+        grantor_signers,
+        freeze_authority,
         schedule,
+        ..
     } = params;
 
     // We only want a one-byte signer nonce
-    let mut signer_nonce = *signer_nonce as u8;
+    let signer_nonce =
+        u8::try_from(*signer_nonce).map_err(|_| TokenVestingError::InvalidSignerNonce)?;
 
     let expected_vesting_contract_account_size =
         VestingContract::compute_allocation_size(schedule.len());
 
     if accounts.vesting_contract.data_len() != expected_vesting_contract_account_size {
-        msg!("The vesting contract account is incorrectly sized for the supplied schedule!");
-        return Err(ProgramError::InvalidArgument);
+        return Err(TokenVestingError::WrongAccountSize.into());
     }
 
-    check_vault_account(
+    let vault_account = check_vault_account(
         accounts.vault,
         program_id,
         *accounts.vesting_contract.key,
         signer_nonce,
     )?;
 
+    let timestamp = Clock::get()?.unix_timestamp;
+    check_accounts(&accounts, &vault_account)?;
+    // Offsets from an unknown activation can't be compared to the current time
+    let current_timestamp = if activator == &Pubkey::default() {
+        Some(timestamp as u64)
+    } else {
+        None
+    };
+    let total_amount = check_schedule(schedule, flags, current_timestamp)?;
+    check_expiry(
+        schedule,
+        *expiry_timestamp,
+        current_timestamp,
+        timestamp as u64,
+    )?;
+    metadata.check()?;
+    let withholding_account = check_withholding(&accounts, &vault_account, *withholding_bps)?;
+    check_milestones(schedule, milestone_approver, milestone_gated)?;
+    // Gated entries can be released out of order, which cumulative quantities can't express
+    if flags.contains(CreateFlag::CumulativeLayout) && milestone_approver != &Pubkey::default() {
+        return Err(TokenVestingError::InvalidMilestones.into());
+    }
+    owner_signers.check()?;
+    grantor_signers.check()?;
+
+    // The payer tops up the account to keep it rent exempt
+    top_up_rent(
+        &accounts,
+        accounts.vesting_contract,
+        accounts.vesting_contract.data_len(),
+    )?;
+
+    initialize_mint_stats(program_id, &accounts, &vault_account.mint)?;
+    if let Some(owner_registry) = accounts.owner_registry {
+        register_contract(program_id, &accounts, owner_registry)?;
+    }
+
     let mut vesting_contract_guard = accounts.vesting_contract.data.borrow_mut();
 
     VestingContract::initialize(&mut vesting_contract_guard)?;
-    let vesting_contract =
+    let mut vesting_contract =
         VestingContract::from_buffer(&mut vesting_contract_guard, state::Tag::VestingContract)?;
 
     *vesting_contract.header = VestingContractHeader {
@@ -122,21 +310,56 @@ pub fn process(program_id: &Pubkey, accounts: &[AccountInfo], params: Params) ->
         vault: *accounts.vault.key,
         current_schedule_index: 0,
         signer_nonce,
-        _padding: [0; 7],
+        version: VestingContractHeader::CURRENT_VERSION,
+        signer_derivation: VestingContractHeader::NAMESPACED_SIGNER_DERIVATION,
+        _padding: [0; 5],
+    };
+    *vesting_contract.extension_mut()? = VestingContractExtension {
+        grantor: *accounts.source_tokens_owner.key,
+        last_acceleration_timestamp: 0,
+        acceleration_count: 0,
+        claimed_amount: 0,
+        mint: vault_account.mint,
+        creation_timestamp: timestamp as u64,
+        total_amount,
+        metadata: *metadata,
+        activator: *activator,
+        activation_timestamp: 0,
+        rent_payer: *accounts.payer.key,
+        expiry_timestamp: *expiry_timestamp,
+        is_expired: 0,
+        is_frozen: 0,
+        is_counted: 1,
+        is_registered: accounts.owner_registry.is_some() as u8,
+        is_cumulative: 0,
+        _padding: [0; 3],
+        split_destinations: [SplitDestination::zeroed();
+            VestingContractExtension::MAX_SPLIT_DESTINATIONS],
+        withholding_account,
+        withholding_bps: *withholding_bps,
+        milestone_approver: *milestone_approver,
+        milestone_gated: *milestone_gated,
+        milestone_approved: MilestoneBitmap::zeroed(),
+        owner_signers: *owner_signers,
+        grantor_signers: *grantor_signers,
+        freeze_authority: *freeze_authority,
+        freeze_timestamp: 0,
+        thaw_timestamp: 0,
+        _reserved: [0; 48],
     };
 
-    let mut total_amount = 0u64;
-    let mut last_timestamp: u64 = 0;
-    for (schedule, slot) in schedule.iter().zip(vesting_contract.schedules.iter_mut()) {
-        if schedule.unlock_timestamp < last_timestamp {
-            msg!("The schedules should be provided in order!");
-            return Err(ProgramError::InvalidArgument);
-        }
-        last_timestamp = schedule.unlock_timestamp;
-        *slot = *schedule;
-        total_amount = total_amount.checked_add(schedule.quantity).unwrap();
+    vesting_contract.schedules.copy_from_slice(schedule);
+    if flags.contains(CreateFlag::CumulativeLayout) {
+        vesting_contract.make_cumulative()?;
     }
 
+    update_mint_stats(
+        program_id,
+        &vesting_contract,
+        Some(accounts.mint_stats),
+        |mint_stats| mint_stats.record_create(total_amount),
+    )?;
+
     let instruction = spl_token::instruction::transfer(
         &spl_token::ID,
         accounts.source_tokens.key,
@@ -156,6 +379,27 @@ pub fn process(program_id: &Pubkey, accounts: &[AccountInfo], params: Params) ->
         ],
     )?;
 
+    Event::Create(CreateEvent {
+        vesting_contract: *accounts.vesting_contract.key,
+        owner: *accounts.recipient.key,
+        grantor: *accounts.source_tokens_owner.key,
+        mint: vault_account.mint,
+        vault: *accounts.vault.key,
+        total_amount,
+        schedule_start_index: 0,
+        schedule_end_index: schedule.len() as u64,
+        timestamp,
+    })
+    .emit();
+
+    Event::Metadata(MetadataEvent {
+        vesting_contract: *accounts.vesting_contract.key,
+        label: metadata.label()?.to_owned(),
+        uri: metadata.uri()?.to_owned(),
+        timestamp,
+    })
+    .emit();
+
     Ok(())
 }
 
@@ -164,11 +408,14 @@ fn check_vault_account(
     program_id: &Pubkey,
     contract_key: Pubkey,
     signer_nonce: u8,
-) -> Result<(), ProgramError> {
+) -> Result<spl_token::state::Account, ProgramError> {
     let vault_account = spl_token::state::Account::unpack(&vault.data.borrow())?;
 
-    let vault_signer =
-        Pubkey::create_program_address(&[&contract_key.to_bytes(), &[signer_nonce]], program_id)?;
+    // Only the canonical bump is accepted
+    let (vault_signer, bump) = VestingContract::find_signer_address(&contract_key, program_id);
+    if signer_nonce != bump {
+        return Err(TokenVestingError::NonCanonicalSignerNonce.into());
+    }
     let is_valid = vault_account.owner == vault_signer
         && vault_account.amount == 0
         && vault_account.delegate.is_none()
@@ -177,5 +424,234 @@ fn check_vault_account(
     if !is_valid {
         return Err(TokenVestingError::InvalidVaultAccount.into());
     }
+    Ok(vault_account)
+}
+
+/// Create the statistics PDA of the vested mint when this is the first contract of the mint
+fn initialize_mint_stats(
+    program_id: &Pubkey,
+    accounts: &Accounts<AccountInfo>,
+    mint: &Pubkey,
+) -> ProgramResult {
+    let (mint_stats_key, bump) = MintStats::find_address(mint, program_id);
+    if accounts.mint_stats.key != &mint_stats_key {
+        return Err(TokenVestingError::WrongMintStats.into());
+    }
+    if !accounts.mint_stats.data_is_empty() {
+        return Ok(());
+    }
+    create_program_account(
+        program_id,
+        accounts,
+        accounts.mint_stats,
+        &[MintStats::SEED, mint.as_ref(), &[bump]],
+        MintStats::ALLOCATION_SIZE,
+    )?;
+    MintStats::initialize(&mut accounts.mint_stats.data.borrow_mut(), mint)?;
+    Ok(())
+}
+
+/// Append the new contract to the registry of the recipient, creating the registry when this is
+/// its first registered contract
+fn register_contract<'a>(
+    program_id: &Pubkey,
+    accounts: &Accounts<AccountInfo<'a>>,
+    owner_registry: &AccountInfo<'a>,
+) -> ProgramResult {
+    let (owner_registry_key, bump) =
+        OwnerRegistry::find_address(accounts.recipient.key, program_id);
+    if owner_registry.key != &owner_registry_key {
+        return Err(TokenVestingError::WrongOwnerRegistry.into());
+    }
+
+    let number_of_contracts = if owner_registry.data_is_empty() {
+        create_program_account(
+            program_id,
+            accounts,
+            owner_registry,
+            &[
+                OwnerRegistry::SEED,
+                accounts.recipient.key.as_ref(),
+                &[bump],
+            ],
+            OwnerRegistry::compute_allocation_size(0),
+        )?;
+        OwnerRegistry::initialize(
+            &mut owner_registry.data.borrow_mut(),
+            accounts.recipient.key,
+        )?;
+        0
+    } else {
+        check_account_owner(owner_registry, program_id)?;
+        OwnerRegistry::from_buffer(&mut owner_registry.data.borrow_mut())?
+            .contracts
+            .len()
+    };
+
+    // The registry grows by one entry, which the payer pays the rent of
+    let allocation_size = OwnerRegistry::compute_allocation_size(number_of_contracts + 1);
+    top_up_rent(accounts, owner_registry, allocation_size)?;
+    owner_registry.realloc(allocation_size, false)?;
+
+    let mut owner_registry_guard = owner_registry.data.borrow_mut();
+    let owner_registry = OwnerRegistry::from_buffer(&mut owner_registry_guard)?;
+    owner_registry.contracts[number_of_contracts] = *accounts.vesting_contract.key;
+    Ok(())
+}
+
+/// Create an account owned by the program at one of its PDAs, paid by the payer
+fn create_program_account<'a>(
+    program_id: &Pubkey,
+    accounts: &Accounts<AccountInfo<'a>>,
+    account: &AccountInfo<'a>,
+    seeds: &[&[u8]],
+    allocation_size: usize,
+) -> ProgramResult {
+    // The PDA may already hold lamports, in which case it can't be created in one go
+    top_up_rent(accounts, account, allocation_size)?;
+    invoke_signed(
+        &system_instruction::allocate(account.key, allocation_size as u64),
+        &[accounts.system_program.clone(), account.clone()],
+        &[seeds],
+    )?;
+    invoke_signed(
+        &system_instruction::assign(account.key, program_id),
+        &[accounts.system_program.clone(), account.clone()],
+        &[seeds],
+    )?;
+    Ok(())
+}
+
+/// Transfer the lamports an account is missing to be rent exempt at the given size from the payer
+fn top_up_rent<'a>(
+    accounts: &Accounts<AccountInfo<'a>>,
+    account: &AccountInfo<'a>,
+    allocation_size: usize,
+) -> ProgramResult {
+    let missing_lamports = Rent::get()?
+        .minimum_balance(allocation_size)
+        .saturating_sub(account.lamports());
+    if missing_lamports != 0 {
+        invoke(
+            &system_instruction::transfer(accounts.payer.key, account.key, missing_lamports),
+            &[
+                accounts.system_program.clone(),
+                accounts.payer.clone(),
+                account.clone(),
+            ],
+        )?;
+    }
+    Ok(())
+}
+
+fn check_accounts(
+    accounts: &Accounts<AccountInfo>,
+    vault_account: &spl_token::state::Account,
+) -> Result<(), ProgramError> {
+    if accounts.recipient.key == &Pubkey::default() {
+        return Err(TokenVestingError::InvalidRecipient.into());
+    }
+
+    let source_tokens_account =
+        spl_token::state::Account::unpack(&accounts.source_tokens.data.borrow())?;
+    if source_tokens_account.mint != vault_account.mint {
+        return Err(TokenVestingError::MintMismatch.into());
+    }
+    Ok(())
+}
+
+/// Validate the schedule and return the total quantity of tokens it vests.
+/// The current timestamp is `None` when the unlocks are relative to a deferred activation.
+fn check_schedule(
+    schedule: &[VestingSchedule],
+    flags: BitFlags<CreateFlag>,
+    current_timestamp: Option<u64>,
+) -> Result<u64, ProgramError> {
+    if schedule.is_empty() {
+        return Err(TokenVestingError::EmptySchedule.into());
+    }
+
+    let mut total_amount = 0u64;
+    let mut last_timestamp: u64 = 0;
+    for s in schedule {
+        if s.unlock_timestamp < last_timestamp {
+            return Err(TokenVestingError::UnsortedSchedule.into());
+        }
+        if s.quantity == 0 {
+            return Err(TokenVestingError::ZeroQuantitySchedule.into());
+        }
+        let is_past = matches!(current_timestamp, Some(t) if s.unlock_timestamp < t);
+        if is_past && !flags.contains(CreateFlag::AllowPastUnlocks) {
+            return Err(TokenVestingError::UnlockInThePast.into());
+        }
+        last_timestamp = s.unlock_timestamp;
+        total_amount = total_amount
+            .checked_add(s.quantity)
+            .ok_or(TokenVestingError::Overflow)?;
+    }
+    Ok(total_amount)
+}
+
+/// Check that an expiry leaves time to claim every unlock. The unlocks of contracts with a
+/// deferred activation are only known once activated, and checked then.
+fn check_expiry(
+    schedule: &[VestingSchedule],
+    expiry_timestamp: u64,
+    current_timestamp: Option<u64>,
+    timestamp: u64,
+) -> Result<(), ProgramError> {
+    if expiry_timestamp == 0 {
+        return Ok(());
+    }
+    let last_unlock = match (current_timestamp, schedule.last()) {
+        (Some(_), Some(s)) => s.unlock_timestamp,
+        _ => 0,
+    };
+    if expiry_timestamp <= timestamp || expiry_timestamp <= last_unlock {
+        return Err(TokenVestingError::InvalidExpiry.into());
+    }
+    Ok(())
+}
+
+/// Check that a withholding token account of the vested mint is passed exactly when part of each
+/// release is withheld, and return its key
+fn check_withholding(
+    accounts: &Accounts<AccountInfo>,
+    vault_account: &spl_token::state::Account,
+    withholding_bps: u64,
+) -> Result<Pubkey, ProgramError> {
+    if withholding_bps > SplitDestination::TOTAL_BPS {
+        return Err(TokenVestingError::InvalidWithholding.into());
+    }
+    let withholding_token_account = match (accounts.withholding_token_account, withholding_bps) {
+        (None, 0) => return Ok(Pubkey::default()),
+        (Some(a), bps) if bps != 0 => a,
+        _ => return Err(TokenVestingError::InvalidWithholding.into()),
+    };
+    if withholding_token_account.key == accounts.vault.key {
+        return Err(TokenVestingError::InvalidWithholding.into());
+    }
+    let withholding_account =
+        spl_token::state::Account::unpack(&withholding_token_account.data.borrow())?;
+    if withholding_account.mint != vault_account.mint {
+        return Err(TokenVestingError::MintMismatch.into());
+    }
+    Ok(*withholding_token_account.key)
+}
+
+/// Check that an approver is set exactly when some schedule entries are gated by a milestone, and
+/// that only existing entries are gated
+fn check_milestones(
+    schedule: &[VestingSchedule],
+    milestone_approver: &Pubkey,
+    milestone_gated: &MilestoneBitmap,
+) -> Result<(), ProgramError> {
+    let has_gated_entries = !milestone_gated.is_empty();
+    let gates_missing_entries =
+        (schedule.len()..MilestoneBitmap::LEN * 8).any(|i| milestone_gated.contains(i));
+    let has_approver = milestone_approver != &Pubkey::default();
+    if gates_missing_entries || has_approver != has_gated_entries {
+        return Err(TokenVestingError::InvalidMilestones.into());
+    }
     Ok(())
 }
@@ -0,0 +1,88 @@
+//! Hold the releases of a vesting contract until it is thawed
+
+use bonfida_utils::checks::{check_account_owner, check_signer};
+use bytemuck::{Pod, Zeroable};
+use solana_program::{clock::Clock, sysvar::Sysvar};
+
+use crate::{
+    error::TokenVestingError,
+    events::{Event, FreezeEvent},
+    state::{self, vesting_contract::VestingContract},
+};
+
+use {
+    bonfida_utils::InstructionsAccount,
+    solana_program::{
+        account_info::{next_account_info, AccountInfo},
+        entrypoint::ProgramResult,
+        program_error::ProgramError,
+        pubkey::Pubkey,
+    },
+};
+
+#[derive(Clone, Copy, Zeroable, Pod)]
+#[repr(C)]
+pub struct Params {}
+
+#[derive(InstructionsAccount)]
+pub struct Accounts<'a, T> {
+    /// The account which stores the [`VestingContract`] data structure
+    #[cons(writable)]
+    pub vesting_contract: &'a T,
+
+    /// The freeze authority of the current vesting contract
+    #[cons(signer)]
+    pub freeze_authority: &'a T,
+}
+
+impl<'a, 'b: 'a> Accounts<'a, AccountInfo<'b>> {
+    pub fn parse(
+        accounts: &'a [AccountInfo<'b>],
+        program_id: &Pubkey,
+    ) -> Result<Self, ProgramError> {
+        let accounts_iter = &mut accounts.iter();
+        let accounts = Accounts {
+            vesting_contract: next_account_info(accounts_iter)?,
+            freeze_authority: next_account_info(accounts_iter)?,
+        };
+
+        // Check owners
+        check_account_owner(accounts.vesting_contract, program_id)?;
+
+        // Check signer
+        check_signer(accounts.freeze_authority)?;
+
+        Ok(accounts)
+    }
+}
+
+pub fn process(program_id: &Pubkey, accounts: &[AccountInfo], _params: &Params) -> ProgramResult {
+    let accounts = Accounts::parse(accounts, program_id)?;
+
+    let mut vesting_contract_guard = accounts.vesting_contract.data.borrow_mut();
+    let mut vesting_contract =
+        VestingContract::from_buffer(&mut vesting_contract_guard, state::Tag::VestingContract)?;
+
+    let extension = vesting_contract.extension_mut()?;
+    if extension.freeze_authority == Pubkey::default()
+        || &extension.freeze_authority != accounts.freeze_authority.key
+    {
+        return Err(TokenVestingError::WrongFreezeAuthority.into());
+    }
+    if extension.is_frozen != 0 {
+        return Err(TokenVestingError::ContractFrozen.into());
+    }
+
+    let timestamp = Clock::get()?.unix_timestamp;
+    extension.is_frozen = 1;
+    extension.freeze_timestamp = timestamp as u64;
+
+    Event::Freeze(FreezeEvent {
+        vesting_contract: *accounts.vesting_contract.key,
+        freeze_authority: *accounts.freeze_authority.key,
+        timestamp,
+    })
+    .emit();
+
+    Ok(())
+}
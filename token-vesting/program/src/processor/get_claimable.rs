@@ -0,0 +1,144 @@
+//! Read-only evaluation of a vesting contract, returned through the return data
+
+use bonfida_utils::checks::check_account_owner;
+use bytemuck::{Pod, Zeroable};
+use solana_program::{
+    clock::Clock,
+    program::{get_return_data, set_return_data},
+    sysvar::Sysvar,
+};
+
+use crate::{
+    error::TokenVestingError,
+    state::{self, vesting_contract::VestingContract},
+};
+
+use {
+    bonfida_utils::InstructionsAccount,
+    solana_program::{
+        account_info::{next_account_info, AccountInfo},
+        entrypoint::ProgramResult,
+        program_error::ProgramError,
+        pubkey::Pubkey,
+    },
+};
+
+#[derive(Clone, Copy, Zeroable, Pod)]
+#[repr(C)]
+pub struct Params {}
+
+#[derive(InstructionsAccount)]
+pub struct Accounts<'a, T> {
+    /// The account which stores the [`VestingContract`] data structure
+    pub vesting_contract: &'a T,
+}
+
+#[derive(Clone, Copy, Zeroable, Pod, Debug, PartialEq, Eq)]
+#[repr(C)]
+/// The status of a vesting contract, written to the return data by the `GetClaimable` instruction
+pub struct VestingStatus {
+    /// The quantity of tokens which can be claimed right now
+    pub claimable_amount: u64,
+    /// The quantity of tokens which are still locked
+    pub locked_amount: u64,
+    /// The quantity of tokens which have already been claimed
+    pub claimed_amount: u64,
+    /// The UTC timestamp of the next unlock, zero if every schedule is unlocked or if the
+    /// contract is waiting for its activation
+    pub next_unlock_timestamp: u64,
+    /// The UTC timestamp the contract was evaluated at
+    pub current_timestamp: u64,
+}
+
+impl VestingStatus {
+    pub const LEN: usize = std::mem::size_of::<Self>();
+}
+
+impl<'a, 'b: 'a> Accounts<'a, AccountInfo<'b>> {
+    pub fn parse(
+        accounts: &'a [AccountInfo<'b>],
+        program_id: &Pubkey,
+    ) -> Result<Self, ProgramError> {
+        let accounts_iter = &mut accounts.iter();
+        let accounts = Accounts {
+            vesting_contract: next_account_info(accounts_iter)?,
+        };
+
+        // Check owners
+        check_account_owner(accounts.vesting_contract, program_id)?;
+
+        Ok(accounts)
+    }
+}
+
+pub fn process(program_id: &Pubkey, accounts: &[AccountInfo], _params: &Params) -> ProgramResult {
+    let accounts = Accounts::parse(accounts, program_id)?;
+
+    // The contract is parsed from a copy so that the account is only borrowed immutably
+    let mut vesting_contract_data = accounts.vesting_contract.data.borrow().to_vec();
+    let vesting_contract =
+        VestingContract::from_buffer(&mut vesting_contract_data, state::Tag::VestingContract)?;
+
+    let current_timestamp = Clock::get()?.unix_timestamp as u64;
+
+    let mut status = VestingStatus {
+        claimable_amount: 0,
+        locked_amount: 0,
+        claimed_amount: vesting_contract
+            .extension
+            .as_ref()
+            .map(|e| e.claimed_amount)
+            .unwrap_or(0),
+        next_unlock_timestamp: 0,
+        current_timestamp,
+    };
+
+    // Nothing remains for the owner once the contract expires
+    let first_remaining_index = if vesting_contract.is_expired(current_timestamp) {
+        vesting_contract.schedules.len()
+    } else {
+        vesting_contract.first_unclaimed_index()
+    };
+
+    // Contracts waiting for their activation are fully locked
+    let schedule_time = vesting_contract.schedule_time(current_timestamp);
+
+    for (i, s) in vesting_contract
+        .schedules
+        .iter()
+        .enumerate()
+        .skip(first_remaining_index)
+    {
+        let is_unlocked = matches!(schedule_time, Some(t) if s.unlock_timestamp <= t);
+        if !is_unlocked && status.next_unlock_timestamp == 0 {
+            status.next_unlock_timestamp = vesting_contract.unlock_timestamp(s).unwrap_or(0);
+        }
+        // Unlocked entries waiting for their milestone or held by a freeze stay locked
+        let is_held = vesting_contract.is_frozen() || vesting_contract.is_awaiting_milestone(i);
+        if is_unlocked && !is_held {
+            status.claimable_amount = status
+                .claimable_amount
+                .checked_add(vesting_contract.schedule_quantity(i))
+                .ok_or(TokenVestingError::Overflow)?;
+        } else {
+            status.locked_amount = status
+                .locked_amount
+                .checked_add(vesting_contract.schedule_quantity(i))
+                .ok_or(TokenVestingError::Overflow)?;
+        }
+    }
+
+    set_return_data(bytemuck::bytes_of(&status));
+
+    Ok(())
+}
+
+/// Read the [`VestingStatus`] left in the return data by a previous `GetClaimable` instruction
+pub fn read_return_data() -> Result<VestingStatus, ProgramError> {
+    match get_return_data() {
+        Some((program_id, data)) if program_id == crate::ID && data.len() == VestingStatus::LEN => {
+            Ok(bytemuck::pod_read_unaligned(&data))
+        }
+        _ => Err(TokenVestingError::InvalidReturnData.into()),
+    }
+}
@@ -0,0 +1,152 @@
+//! Upgrade a legacy vesting contract account to the current layout
+
+use bonfida_utils::checks::{check_account_key, check_account_owner, check_signer};
+use bytemuck::{Pod, Zeroable};
+use solana_program::{
+    clock::Clock, program::invoke, program_pack::Pack, rent::Rent, system_instruction,
+    system_program, sysvar::Sysvar,
+};
+
+use crate::{
+    error::TokenVestingError,
+    events::{Event, MigrateEvent},
+    state::{
+        self,
+        vesting_contract::{
+            LegacyVestingContractHeader, VestingContract, VestingContractExtension,
+            VestingContractHeader,
+        },
+    },
+};
+
+use {
+    bonfida_utils::InstructionsAccount,
+    solana_program::{
+        account_info::{next_account_info, AccountInfo},
+        entrypoint::ProgramResult,
+        program_error::ProgramError,
+        pubkey::Pubkey,
+    },
+};
+
+#[derive(Clone, Copy, Zeroable, Pod)]
+#[repr(C)]
+pub struct Params {}
+
+#[derive(InstructionsAccount)]
+pub struct Accounts<'a, T> {
+    /// The system program account
+    pub system_program: &'a T,
+
+    /// The account which stores the [`VestingContract`] data structure
+    #[cons(writable)]
+    pub vesting_contract: &'a T,
+
+    /// The contract's escrow vault
+    pub vault: &'a T,
+
+    /// The account paying for the additional rent
+    #[cons(writable, signer)]
+    pub payer: &'a T,
+}
+
+impl<'a, 'b: 'a> Accounts<'a, AccountInfo<'b>> {
+    pub fn parse(
+        accounts: &'a [AccountInfo<'b>],
+        program_id: &Pubkey,
+    ) -> Result<Self, ProgramError> {
+        let accounts_iter = &mut accounts.iter();
+        let accounts = Accounts {
+            system_program: next_account_info(accounts_iter)?,
+            vesting_contract: next_account_info(accounts_iter)?,
+            vault: next_account_info(accounts_iter)?,
+            payer: next_account_info(accounts_iter)?,
+        };
+
+        // Check keys
+        check_account_key(accounts.system_program, &system_program::ID)?;
+
+        // Check owners
+        check_account_owner(accounts.vesting_contract, program_id)?;
+        check_account_owner(accounts.vault, &spl_token::ID)?;
+
+        // Check signer
+        check_signer(accounts.payer)?;
+
+        Ok(accounts)
+    }
+}
+
+pub fn process(program_id: &Pubkey, accounts: &[AccountInfo], _params: &Params) -> ProgramResult {
+    let accounts = Accounts::parse(accounts, program_id)?;
+
+    let legacy_header = {
+        let mut vesting_contract_guard = accounts.vesting_contract.data.borrow_mut();
+        let vesting_contract =
+            VestingContract::from_buffer(&mut vesting_contract_guard, state::Tag::VestingContract)?;
+        if vesting_contract.extension.is_some() {
+            return Err(TokenVestingError::AlreadyMigrated.into());
+        }
+        if &vesting_contract.header.vault != accounts.vault.key {
+            return Err(TokenVestingError::WrongVault.into());
+        }
+        let legacy_header: LegacyVestingContractHeader =
+            *bytemuck::from_bytes(&vesting_contract_guard[8..8 + LegacyVestingContractHeader::LEN]);
+        legacy_header
+    };
+    let vault_account = spl_token::state::Account::unpack(&accounts.vault.data.borrow())?;
+
+    let legacy_len = accounts.vesting_contract.data_len();
+    let new_len = legacy_len
+        .checked_add(VestingContractExtension::LEN)
+        .ok_or(TokenVestingError::Overflow)?;
+
+    // The payer tops up the account to keep it rent exempt
+    let missing_lamports = Rent::get()?
+        .minimum_balance(new_len)
+        .saturating_sub(accounts.vesting_contract.lamports());
+    if missing_lamports != 0 {
+        invoke(
+            &system_instruction::transfer(
+                accounts.payer.key,
+                accounts.vesting_contract.key,
+                missing_lamports,
+            ),
+            &[
+                accounts.system_program.clone(),
+                accounts.payer.clone(),
+                accounts.vesting_contract.clone(),
+            ],
+        )?;
+    }
+
+    accounts.vesting_contract.realloc(new_len, false)?;
+
+    // The schedules are moved after the extension, which is then zeroed out, and the header is
+    // rewritten with the current layout
+    let mut data = accounts.vesting_contract.data.borrow_mut();
+    let extension_start = 8 + LegacyVestingContractHeader::LEN;
+    let extension_end = extension_start + VestingContractExtension::LEN;
+    data.copy_within(extension_start..legacy_len, extension_end);
+    data[extension_start..extension_end].fill(0);
+
+    data[8..extension_start].copy_from_slice(bytemuck::bytes_of(&legacy_header.upgrade()));
+
+    let mut vesting_contract =
+        VestingContract::from_buffer(&mut data, state::Tag::VestingContract)?;
+
+    // Legacy accounts don't record what was claimed, so the total starts from what remains
+    let total_amount = vesting_contract.remaining_amount()?;
+    let extension = vesting_contract.extension_mut()?;
+    extension.mint = vault_account.mint;
+    extension.total_amount = total_amount;
+
+    Event::Migrate(MigrateEvent {
+        vesting_contract: *accounts.vesting_contract.key,
+        version: VestingContractHeader::CURRENT_VERSION,
+        timestamp: Clock::get()?.unix_timestamp,
+    })
+    .emit();
+
+    Ok(())
+}
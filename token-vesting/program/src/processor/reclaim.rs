@@ -0,0 +1,202 @@
+//! Return the unclaimed tokens of an expired vesting contract to the grantor
+
+use bonfida_utils::checks::{check_account_key, check_account_owner};
+use bytemuck::{Pod, Zeroable};
+use solana_program::{clock::Clock, program::invoke_signed, program_pack::Pack, sysvar::Sysvar};
+
+use crate::{
+    error::TokenVestingError,
+    events::{Event, ReclaimEvent},
+    processor::{check_authority, next_mint_stats_account, update_mint_stats},
+    state::{
+        self,
+        vesting_contract::{Authority, VestingContract},
+    },
+};
+
+use {
+    bonfida_utils::InstructionsAccount,
+    solana_program::{
+        account_info::{next_account_info, AccountInfo},
+        entrypoint::ProgramResult,
+        program_error::ProgramError,
+        pubkey::Pubkey,
+    },
+};
+
+#[derive(Clone, Copy, Zeroable, Pod)]
+#[repr(C)]
+pub struct Params {}
+
+#[derive(InstructionsAccount)]
+pub struct Accounts<'a, T> {
+    /// SPL token program account
+    pub spl_token_program: &'a T,
+
+    /// The account which stores the [`VestingContract`] data structure
+    #[cons(writable)]
+    pub vesting_contract: &'a T,
+
+    /// The signing PDA which owns the vault
+    pub vesting_contract_signer: &'a T,
+
+    /// The contract's escrow vault
+    #[cons(writable)]
+    pub vault: &'a T,
+
+    /// The token account of the grantor which receives the unclaimed tokens
+    #[cons(writable)]
+    pub destination_token_account: &'a T,
+
+    /// The grantor of the current vesting contract
+    #[cons(signer)]
+    pub grantor: &'a T,
+
+    /// The [`MintStats`](crate::state::mint_stats::MintStats) PDA of the mint of the contract,
+    /// only passed for contracts counted in the statistics
+    #[cons(writable)]
+    pub mint_stats: Option<&'a T>,
+
+    /// The keys of the signer set of the grantor which sign the instruction, when the grantor is an
+    /// M-of-N set
+    #[cons(signer)]
+    pub co_signers: &'a [T],
+}
+
+impl<'a, 'b: 'a> Accounts<'a, AccountInfo<'b>> {
+    pub fn parse(
+        accounts: &'a [AccountInfo<'b>],
+        program_id: &Pubkey,
+    ) -> Result<Self, ProgramError> {
+        let accounts_iter = &mut accounts.iter();
+        let mut accounts = Accounts {
+            spl_token_program: next_account_info(accounts_iter)?,
+            vesting_contract: next_account_info(accounts_iter)?,
+            vesting_contract_signer: next_account_info(accounts_iter)?,
+            vault: next_account_info(accounts_iter)?,
+            destination_token_account: next_account_info(accounts_iter)?,
+            grantor: next_account_info(accounts_iter)?,
+            mint_stats: None,
+            co_signers: &[],
+        };
+
+        // Check keys
+        check_account_key(accounts.spl_token_program, &spl_token::ID)?;
+
+        // Check owners
+        check_account_owner(accounts.vesting_contract, program_id)?;
+        check_account_owner(accounts.vault, &spl_token::ID)?;
+        check_account_owner(accounts.destination_token_account, &spl_token::ID)?;
+
+        accounts.mint_stats = next_mint_stats_account(accounts.vesting_contract, accounts_iter)?;
+        accounts.co_signers = accounts_iter.as_slice();
+
+        // Check signer
+        check_authority(
+            accounts.vesting_contract,
+            Authority::Grantor,
+            accounts.grantor,
+            accounts.co_signers,
+        )?;
+
+        Ok(accounts)
+    }
+}
+
+pub fn process(program_id: &Pubkey, accounts: &[AccountInfo], _params: &Params) -> ProgramResult {
+    let accounts = Accounts::parse(accounts, program_id)?;
+
+    let mut vesting_contract_guard = accounts.vesting_contract.data.borrow_mut();
+    let mut vesting_contract =
+        VestingContract::from_buffer(&mut vesting_contract_guard, state::Tag::VestingContract)?;
+
+    if vesting_contract.grantor()? != accounts.grantor.key {
+        return Err(TokenVestingError::WrongGrantor.into());
+    }
+    if &vesting_contract.header.vault != accounts.vault.key {
+        return Err(TokenVestingError::WrongVault.into());
+    }
+
+    let contract_signer_key = Pubkey::create_program_address(
+        &vesting_contract
+            .header
+            .signer_seeds(accounts.vesting_contract.key),
+        program_id,
+    )?;
+    if &contract_signer_key != accounts.vesting_contract_signer.key {
+        return Err(TokenVestingError::WrongContractSigner.into());
+    }
+
+    // The freeze holds the vault as a whole, including what is left to the grantor
+    if vesting_contract.is_frozen() {
+        return Err(TokenVestingError::ContractFrozen.into());
+    }
+
+    let timestamp = Clock::get()?.unix_timestamp;
+    let remaining_amount = vesting_contract.remaining_amount()?;
+    let is_fully_claimed = vesting_contract.is_fully_claimed();
+    let extension = vesting_contract.extension_mut()?;
+    if extension.expiry_timestamp == 0 {
+        return Err(TokenVestingError::NotExpirable.into());
+    }
+    if (timestamp as u64) < extension.expiry_timestamp {
+        return Err(TokenVestingError::NotExpired.into());
+    }
+    let was_reclaimed = extension.is_expired != 0;
+    extension.is_expired = 1;
+
+    // The statistics only account for the first reclaim, later ones merely collect stray tokens
+    if !was_reclaimed {
+        update_mint_stats(
+            program_id,
+            &vesting_contract,
+            accounts.mint_stats,
+            |mint_stats| mint_stats.record_reclaim(remaining_amount, !is_fully_claimed),
+        )?;
+    }
+
+    let vault_account = spl_token::state::Account::unpack(&accounts.vault.data.borrow())?;
+    let destination_account =
+        spl_token::state::Account::unpack(&accounts.destination_token_account.data.borrow())?;
+    if destination_account.mint != vault_account.mint {
+        return Err(TokenVestingError::MintMismatch.into());
+    }
+    if &destination_account.owner != accounts.grantor.key {
+        return Err(TokenVestingError::WrongDestinationOwner.into());
+    }
+
+    // Every remaining token goes back to the grantor, whether it is scheduled or not
+    let transfer_instruction = spl_token::instruction::transfer(
+        &spl_token::ID,
+        accounts.vault.key,
+        accounts.destination_token_account.key,
+        accounts.vesting_contract_signer.key,
+        &[],
+        vault_account.amount,
+    )?;
+
+    invoke_signed(
+        &transfer_instruction,
+        &[
+            accounts.spl_token_program.clone(),
+            accounts.vault.clone(),
+            accounts.destination_token_account.clone(),
+            accounts.vesting_contract_signer.clone(),
+        ],
+        &[&vesting_contract
+            .header
+            .signer_seeds(accounts.vesting_contract.key)],
+    )?;
+
+    Event::Reclaim(ReclaimEvent {
+        vesting_contract: *accounts.vesting_contract.key,
+        grantor: *accounts.grantor.key,
+        mint: vault_account.mint,
+        destination: *accounts.destination_token_account.key,
+        amount: vault_account.amount,
+        timestamp,
+    })
+    .emit();
+
+    Ok(())
+}
@@ -0,0 +1,103 @@
+//! Update the label and metadata URI of a vesting contract
+
+use bonfida_utils::checks::check_account_owner;
+use bytemuck::{Pod, Zeroable};
+use solana_program::{clock::Clock, sysvar::Sysvar};
+
+use crate::{
+    error::TokenVestingError,
+    events::{Event, MetadataEvent},
+    processor::check_authority,
+    state::{
+        self,
+        vesting_contract::{Authority, ContractMetadata, VestingContract},
+    },
+};
+
+use {
+    bonfida_utils::InstructionsAccount,
+    solana_program::{
+        account_info::{next_account_info, AccountInfo},
+        entrypoint::ProgramResult,
+        program_error::ProgramError,
+        pubkey::Pubkey,
+    },
+};
+
+#[derive(Clone, Copy, Zeroable, Pod)]
+#[repr(C)]
+pub struct Params {
+    /// The new label and metadata URI, which replace the previous ones
+    pub metadata: ContractMetadata,
+}
+
+#[derive(InstructionsAccount)]
+pub struct Accounts<'a, T> {
+    /// The account which stores the [`VestingContract`] data structure
+    #[cons(writable)]
+    pub vesting_contract: &'a T,
+
+    /// The grantor of the current vesting contract
+    #[cons(signer)]
+    pub grantor: &'a T,
+
+    /// The keys of the signer set of the grantor which sign the instruction, when the grantor is an
+    /// M-of-N set
+    #[cons(signer)]
+    pub co_signers: &'a [T],
+}
+
+impl<'a, 'b: 'a> Accounts<'a, AccountInfo<'b>> {
+    pub fn parse(
+        accounts: &'a [AccountInfo<'b>],
+        program_id: &Pubkey,
+    ) -> Result<Self, ProgramError> {
+        let accounts_iter = &mut accounts.iter();
+        let accounts = Accounts {
+            vesting_contract: next_account_info(accounts_iter)?,
+            grantor: next_account_info(accounts_iter)?,
+            co_signers: accounts_iter.as_slice(),
+        };
+
+        // Check owners
+        check_account_owner(accounts.vesting_contract, program_id)?;
+
+        // Check signer
+        check_authority(
+            accounts.vesting_contract,
+            Authority::Grantor,
+            accounts.grantor,
+            accounts.co_signers,
+        )?;
+
+        Ok(accounts)
+    }
+}
+
+pub fn process(program_id: &Pubkey, accounts: &[AccountInfo], params: &Params) -> ProgramResult {
+    let accounts = Accounts::parse(accounts, program_id)?;
+
+    let Params { metadata } = params;
+    metadata.check()?;
+
+    let mut vesting_contract_guard = accounts.vesting_contract.data.borrow_mut();
+    let mut vesting_contract =
+        VestingContract::from_buffer(&mut vesting_contract_guard, state::Tag::VestingContract)?;
+
+    if vesting_contract.grantor()? != accounts.grantor.key {
+        return Err(TokenVestingError::WrongGrantor.into());
+    }
+
+    // Legacy accounts have no room for the metadata and need to be migrated first
+    vesting_contract.extension_mut()?.metadata = *metadata;
+
+    Event::Metadata(MetadataEvent {
+        vesting_contract: *accounts.vesting_contract.key,
+        label: metadata.label()?.to_owned(),
+        uri: metadata.uri()?.to_owned(),
+        timestamp: Clock::get()?.unix_timestamp,
+    })
+    .emit();
+
+    Ok(())
+}
@@ -0,0 +1,111 @@
+//! Register the accounts each release of a vesting contract is divided between
+
+use bonfida_utils::checks::check_account_owner;
+use bytemuck::{Pod, Zeroable};
+use solana_program::{clock::Clock, sysvar::Sysvar};
+
+use crate::{
+    error::TokenVestingError,
+    events::{Event, SplitEvent},
+    processor::check_authority,
+    state::{
+        self,
+        vesting_contract::{
+            Authority, SplitDestination, VestingContract, VestingContractExtension,
+        },
+    },
+};
+
+use {
+    bonfida_utils::InstructionsAccount,
+    solana_program::{
+        account_info::{next_account_info, AccountInfo},
+        entrypoint::ProgramResult,
+        program_error::ProgramError,
+        pubkey::Pubkey,
+    },
+};
+
+#[derive(Clone, Copy, Zeroable, Pod)]
+#[repr(C)]
+pub struct Params {
+    /// The new destinations, which replace the previous ones. Unused entries are zeroed out and
+    /// come last. Zeroing out every entry sends each release to a single destination again.
+    pub destinations: [SplitDestination; VestingContractExtension::MAX_SPLIT_DESTINATIONS],
+}
+
+#[derive(InstructionsAccount)]
+pub struct Accounts<'a, T> {
+    /// The account which stores the [`VestingContract`] data structure
+    #[cons(writable)]
+    pub vesting_contract: &'a T,
+
+    /// The owner of the current vesting contract
+    #[cons(signer)]
+    pub owner: &'a T,
+
+    /// The keys of the signer set of the owner which sign the instruction, when the owner is an
+    /// M-of-N set
+    #[cons(signer)]
+    pub co_signers: &'a [T],
+}
+
+impl<'a, 'b: 'a> Accounts<'a, AccountInfo<'b>> {
+    pub fn parse(
+        accounts: &'a [AccountInfo<'b>],
+        program_id: &Pubkey,
+    ) -> Result<Self, ProgramError> {
+        let accounts_iter = &mut accounts.iter();
+        let accounts = Accounts {
+            vesting_contract: next_account_info(accounts_iter)?,
+            owner: next_account_info(accounts_iter)?,
+            co_signers: accounts_iter.as_slice(),
+        };
+
+        // Check owners
+        check_account_owner(accounts.vesting_contract, program_id)?;
+
+        // Check signer
+        check_authority(
+            accounts.vesting_contract,
+            Authority::Owner,
+            accounts.owner,
+            accounts.co_signers,
+        )?;
+
+        Ok(accounts)
+    }
+}
+
+pub fn process(program_id: &Pubkey, accounts: &[AccountInfo], params: &Params) -> ProgramResult {
+    let accounts = Accounts::parse(accounts, program_id)?;
+
+    let Params { destinations } = params;
+    let len = SplitDestination::check_split(destinations)?;
+
+    let mut vesting_contract_guard = accounts.vesting_contract.data.borrow_mut();
+    let mut vesting_contract =
+        VestingContract::from_buffer(&mut vesting_contract_guard, state::Tag::VestingContract)?;
+
+    if &vesting_contract.header.owner != accounts.owner.key {
+        return Err(TokenVestingError::WrongContractOwner.into());
+    }
+    if vesting_contract.is_frozen() {
+        return Err(TokenVestingError::ContractFrozen.into());
+    }
+
+    vesting_contract.extension_mut()?.split_destinations = *destinations;
+
+    Event::Split(SplitEvent {
+        vesting_contract: *accounts.vesting_contract.key,
+        owner: *accounts.owner.key,
+        destinations: destinations[..len]
+            .iter()
+            .map(|d| (d.token_account, d.bps))
+            .collect(),
+        timestamp: Clock::get()?.unix_timestamp,
+    })
+    .emit();
+
+    Ok(())
+}
@@ -0,0 +1,234 @@
+//! Withdraw the tokens of a vault which aren't covered by the remaining schedules
+
+use bonfida_utils::checks::{check_account_key, check_account_owner};
+use bytemuck::{Pod, Zeroable};
+use num_derive::FromPrimitive;
+use num_traits::FromPrimitive;
+use solana_program::{clock::Clock, program::invoke_signed, program_pack::Pack, sysvar::Sysvar};
+
+use crate::{
+    error::TokenVestingError,
+    events::{Event, SweepEvent},
+    processor::{check_authority, next_mint_stats_account, update_mint_stats},
+    state::{
+        self,
+        vesting_contract::{Authority, VestingContract},
+    },
+};
+
+use {
+    bonfida_utils::InstructionsAccount,
+    solana_program::{
+        account_info::{next_account_info, AccountInfo},
+        entrypoint::ProgramResult,
+        program_error::ProgramError,
+        pubkey::Pubkey,
+    },
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, FromPrimitive)]
+/// The account which receives the excess tokens
+pub enum SweepRecipient {
+    /// The excess is returned to the grantor
+    Grantor = 0,
+    /// The excess is sent to the owner as an immediately vested bonus, unless the contract is
+    /// frozen or withholds part of its releases
+    Owner = 1,
+}
+
+#[derive(Clone, Copy, Zeroable, Pod)]
+#[repr(C)]
+pub struct Params {
+    /// A [`SweepRecipient`] value
+    pub recipient: u64,
+}
+
+#[derive(InstructionsAccount)]
+pub struct Accounts<'a, T> {
+    /// SPL token program account
+    pub spl_token_program: &'a T,
+
+    /// The account which stores the [`VestingContract`] data structure
+    #[cons(writable)]
+    pub vesting_contract: &'a T,
+
+    /// The signing PDA which owns the vault
+    pub vesting_contract_signer: &'a T,
+
+    /// The contract's escrow vault
+    #[cons(writable)]
+    pub vault: &'a T,
+
+    /// The token account of the recipient of the excess tokens
+    #[cons(writable)]
+    pub destination_token_account: &'a T,
+
+    /// The grantor of the current vesting contract
+    #[cons(signer)]
+    pub grantor: &'a T,
+
+    /// The [`MintStats`](crate::state::mint_stats::MintStats) PDA of the mint of the contract,
+    /// only passed for contracts counted in the statistics
+    #[cons(writable)]
+    pub mint_stats: Option<&'a T>,
+
+    /// The keys of the signer set of the grantor which sign the instruction, when the grantor is an
+    /// M-of-N set
+    #[cons(signer)]
+    pub co_signers: &'a [T],
+}
+
+impl<'a, 'b: 'a> Accounts<'a, AccountInfo<'b>> {
+    pub fn parse(
+        accounts: &'a [AccountInfo<'b>],
+        program_id: &Pubkey,
+    ) -> Result<Self, ProgramError> {
+        let accounts_iter = &mut accounts.iter();
+        let mut accounts = Accounts {
+            spl_token_program: next_account_info(accounts_iter)?,
+            vesting_contract: next_account_info(accounts_iter)?,
+            vesting_contract_signer: next_account_info(accounts_iter)?,
+            vault: next_account_info(accounts_iter)?,
+            destination_token_account: next_account_info(accounts_iter)?,
+            grantor: next_account_info(accounts_iter)?,
+            mint_stats: None,
+            co_signers: &[],
+        };
+
+        // Check keys
+        check_account_key(accounts.spl_token_program, &spl_token::ID)?;
+
+        // Check owners
+        check_account_owner(accounts.vesting_contract, program_id)?;
+        check_account_owner(accounts.vault, &spl_token::ID)?;
+        check_account_owner(accounts.destination_token_account, &spl_token::ID)?;
+
+        accounts.mint_stats = next_mint_stats_account(accounts.vesting_contract, accounts_iter)?;
+        accounts.co_signers = accounts_iter.as_slice();
+
+        // Check signer
+        check_authority(
+            accounts.vesting_contract,
+            Authority::Grantor,
+            accounts.grantor,
+            accounts.co_signers,
+        )?;
+
+        Ok(accounts)
+    }
+}
+
+pub fn process(program_id: &Pubkey, accounts: &[AccountInfo], params: &Params) -> ProgramResult {
+    let accounts = Accounts::parse(accounts, program_id)?;
+
+    let recipient = SweepRecipient::from_u64(params.recipient)
+        .ok_or(TokenVestingError::InvalidSweepRecipient)?;
+
+    let mut vesting_contract_guard = accounts.vesting_contract.data.borrow_mut();
+    let mut vesting_contract =
+        VestingContract::from_buffer(&mut vesting_contract_guard, state::Tag::VestingContract)?;
+
+    if vesting_contract.grantor()? != accounts.grantor.key {
+        return Err(TokenVestingError::WrongGrantor.into());
+    }
+    if &vesting_contract.header.vault != accounts.vault.key {
+        return Err(TokenVestingError::WrongVault.into());
+    }
+
+    let contract_signer_key = Pubkey::create_program_address(
+        &vesting_contract
+            .header
+            .signer_seeds(accounts.vesting_contract.key),
+        program_id,
+    )?;
+    if &contract_signer_key != accounts.vesting_contract_signer.key {
+        return Err(TokenVestingError::WrongContractSigner.into());
+    }
+
+    // A bonus is released right away, which a freeze holds like any other release
+    if recipient == SweepRecipient::Owner && vesting_contract.is_frozen() {
+        return Err(TokenVestingError::ContractFrozen.into());
+    }
+
+    // A bonus would bypass the share of each release withheld by the grantor
+    if recipient == SweepRecipient::Owner && vesting_contract.withholding_account().is_some() {
+        return Err(TokenVestingError::BonusOnWithheldContract.into());
+    }
+
+    let vault_account = spl_token::state::Account::unpack(&accounts.vault.data.borrow())?;
+    let destination_account =
+        spl_token::state::Account::unpack(&accounts.destination_token_account.data.borrow())?;
+
+    if destination_account.mint != vault_account.mint {
+        return Err(TokenVestingError::MintMismatch.into());
+    }
+    let expected_destination_owner = match recipient {
+        SweepRecipient::Grantor => *vesting_contract.grantor()?,
+        SweepRecipient::Owner => vesting_contract.header.owner,
+    };
+    if destination_account.owner != expected_destination_owner {
+        return Err(TokenVestingError::WrongDestinationOwner.into());
+    }
+
+    // Tokens which are still scheduled are never swept, whether they are locked or not
+    let remaining_amount = vesting_contract.remaining_amount()?;
+    let excess_amount = vault_account.amount.saturating_sub(remaining_amount);
+
+    if excess_amount == 0 {
+        return Err(TokenVestingError::NoExcessTokens.into());
+    }
+
+    // A bonus is accounted for as tokens vested and claimed right away
+    if recipient == SweepRecipient::Owner {
+        let extension = vesting_contract.extension_mut()?;
+        extension.claimed_amount = extension
+            .claimed_amount
+            .checked_add(excess_amount)
+            .ok_or(TokenVestingError::Overflow)?;
+        extension.total_amount = extension
+            .total_amount
+            .checked_add(excess_amount)
+            .ok_or(TokenVestingError::Overflow)?;
+        update_mint_stats(
+            program_id,
+            &vesting_contract,
+            accounts.mint_stats,
+            |mint_stats| mint_stats.record_bonus(excess_amount),
+        )?;
+    }
+
+    let transfer_instruction = spl_token::instruction::transfer(
+        &spl_token::ID,
+        accounts.vault.key,
+        accounts.destination_token_account.key,
+        accounts.vesting_contract_signer.key,
+        &[],
+        excess_amount,
+    )?;
+
+    invoke_signed(
+        &transfer_instruction,
+        &[
+            accounts.spl_token_program.clone(),
+            accounts.vault.clone(),
+            accounts.destination_token_account.clone(),
+            accounts.vesting_contract_signer.clone(),
+        ],
+        &[&vesting_contract
+            .header
+            .signer_seeds(accounts.vesting_contract.key)],
+    )?;
+
+    Event::Sweep(SweepEvent {
+        vesting_contract: *accounts.vesting_contract.key,
+        grantor: *accounts.grantor.key,
+        mint: vault_account.mint,
+        destination: *accounts.destination_token_account.key,
+        amount: excess_amount,
+        is_bonus: recipient == SweepRecipient::Owner,
+        timestamp: Clock::get()?.unix_timestamp,
+    })
+    .emit();
+
+    Ok(())
+}
@@ -3,6 +3,8 @@ use {
     borsh::{BorshDeserialize, BorshSerialize},
 };
 
+pub mod mint_stats;
+pub mod owner_registry;
 pub mod vesting_contract;
 
 #[derive(BorshSerialize, BorshDeserialize, BorshSize, PartialEq)]
@@ -11,4 +13,6 @@ pub enum Tag {
     Uninitialized,
     VestingContract,
     ExampleStateBorsh,
+    MintStats,
+    OwnerRegistry,
 }
@@ -0,0 +1,127 @@
+use bytemuck::{Pod, Zeroable};
+use solana_program::pubkey::Pubkey;
+
+use crate::error::TokenVestingError;
+
+#[derive(Clone, Copy, Zeroable, Pod, Debug, PartialEq)]
+#[repr(C)]
+/// Aggregate counters of the vesting contracts of a mint, held by a PDA of the mint. Contracts
+/// migrated from the legacy layout aren't counted.
+pub struct MintStats {
+    /// The mint the counters are about
+    pub mint: Pubkey,
+    /// The quantity of tokens in the schedules which haven't been released nor reclaimed yet
+    pub total_locked: u64,
+    /// The quantity of tokens released to the owners so far, including the withheld shares
+    pub total_claimed: u64,
+    /// The number of contracts which are neither fully claimed nor reclaimed
+    pub active_contracts: u64,
+    /// The number of contracts created for the mint
+    pub total_contracts: u64,
+}
+
+impl MintStats {
+    pub const LEN: usize = std::mem::size_of::<Self>();
+
+    /// The prefix of the seeds of the statistics PDA of a mint
+    pub const SEED: &'static [u8] = b"mint_stats";
+
+    /// Size of the statistics account, tag included
+    pub const ALLOCATION_SIZE: usize = 8 + Self::LEN;
+
+    /// Find the PDA holding the statistics of a mint, along with its canonical bump
+    pub fn find_address(mint: &Pubkey, program_id: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[Self::SEED, mint.as_ref()], program_id)
+    }
+
+    /// Initialize a new statistics data account for a mint
+    pub fn initialize(buffer: &mut [u8], mint: &Pubkey) -> Result<(), TokenVestingError> {
+        if buffer.len() != Self::ALLOCATION_SIZE {
+            return Err(TokenVestingError::DataTypeMismatch);
+        }
+        let (tag, buffer) = buffer.split_at_mut(8);
+        let tag: &mut u64 = bytemuck::from_bytes_mut(tag);
+        if *tag != super::Tag::Uninitialized as u64 {
+            return Err(TokenVestingError::DataTypeMismatch);
+        }
+        *tag = super::Tag::MintStats as u64;
+        let stats: &mut Self = bytemuck::from_bytes_mut(buffer);
+        *stats = Self {
+            mint: *mint,
+            ..Self::zeroed()
+        };
+        Ok(())
+    }
+
+    /// Cast the buffer as a MintStats reference
+    pub fn from_buffer(buffer: &mut [u8]) -> Result<&mut Self, TokenVestingError> {
+        if buffer.len() != Self::ALLOCATION_SIZE {
+            return Err(TokenVestingError::DataTypeMismatch);
+        }
+        let (tag, buffer) = buffer.split_at_mut(8);
+        if *bytemuck::from_bytes_mut::<u64>(tag) != super::Tag::MintStats as u64 {
+            return Err(TokenVestingError::DataTypeMismatch);
+        }
+        Ok(bytemuck::from_bytes_mut(buffer))
+    }
+
+    /// Decode the statistics from the data of the account, as fetched by a client
+    pub fn read(data: &[u8]) -> Result<Self, TokenVestingError> {
+        if data.len() != Self::ALLOCATION_SIZE {
+            return Err(TokenVestingError::DataTypeMismatch);
+        }
+        if bytemuck::pod_read_unaligned::<u64>(&data[..8]) != super::Tag::MintStats as u64 {
+            return Err(TokenVestingError::DataTypeMismatch);
+        }
+        Ok(bytemuck::pod_read_unaligned(&data[8..]))
+    }
+
+    /// Record a new contract locking `amount` tokens
+    pub fn record_create(&mut self, amount: u64) -> Result<(), TokenVestingError> {
+        self.total_locked = add(self.total_locked, amount)?;
+        self.active_contracts = add(self.active_contracts, 1)?;
+        self.total_contracts = add(self.total_contracts, 1)?;
+        Ok(())
+    }
+
+    /// Record the release of `amount` locked tokens to the owner of a contract
+    pub fn record_claim(
+        &mut self,
+        amount: u64,
+        is_fully_claimed: bool,
+    ) -> Result<(), TokenVestingError> {
+        self.total_locked = sub(self.total_locked, amount)?;
+        self.total_claimed = add(self.total_claimed, amount)?;
+        if is_fully_claimed {
+            self.active_contracts = sub(self.active_contracts, 1)?;
+        }
+        Ok(())
+    }
+
+    /// Record a transfer of tokens in excess of the schedules to the owner of a contract
+    pub fn record_bonus(&mut self, amount: u64) -> Result<(), TokenVestingError> {
+        self.total_claimed = add(self.total_claimed, amount)?;
+        Ok(())
+    }
+
+    /// Record the grantor reclaiming the `amount` tokens still locked in a contract
+    pub fn record_reclaim(
+        &mut self,
+        amount: u64,
+        was_active: bool,
+    ) -> Result<(), TokenVestingError> {
+        self.total_locked = sub(self.total_locked, amount)?;
+        if was_active {
+            self.active_contracts = sub(self.active_contracts, 1)?;
+        }
+        Ok(())
+    }
+}
+
+fn add(a: u64, b: u64) -> Result<u64, TokenVestingError> {
+    a.checked_add(b).ok_or(TokenVestingError::Overflow)
+}
+
+fn sub(a: u64, b: u64) -> Result<u64, TokenVestingError> {
+    a.checked_sub(b).ok_or(TokenVestingError::Overflow)
+}
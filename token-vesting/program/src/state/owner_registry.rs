@@ -0,0 +1,74 @@
+use bytemuck::{Pod, Zeroable};
+use solana_program::pubkey::Pubkey;
+
+use crate::error::TokenVestingError;
+
+/// The list of the vesting contracts of an owner, held by a PDA of the owner so that wallets can
+/// find their grants without scanning the program accounts. It grows with each registered
+/// contract.
+pub struct OwnerRegistry<'a> {
+    pub header: &'a mut OwnerRegistryHeader,
+    /// The keys of the registered contracts, in registration order
+    pub contracts: &'a mut [Pubkey],
+}
+
+#[derive(Clone, Copy, Zeroable, Pod)]
+#[repr(C)]
+pub struct OwnerRegistryHeader {
+    /// The owner of the registered contracts
+    pub owner: Pubkey,
+}
+
+impl OwnerRegistryHeader {
+    pub const LEN: usize = std::mem::size_of::<Self>();
+}
+
+impl<'a> OwnerRegistry<'a> {
+    /// The prefix of the seeds of the registry PDA of an owner
+    pub const SEED: &'static [u8] = b"owner_registry";
+
+    /// Find the PDA holding the registry of an owner, along with its canonical bump
+    pub fn find_address(owner: &Pubkey, program_id: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[Self::SEED, owner.as_ref()], program_id)
+    }
+
+    /// Size of a registry account listing `number_of_contracts` contracts, tag included
+    pub fn compute_allocation_size(number_of_contracts: usize) -> usize {
+        8 + OwnerRegistryHeader::LEN + number_of_contracts * 32
+    }
+
+    /// Initialize a new registry data account for an owner
+    pub fn initialize(buffer: &mut [u8], owner: &Pubkey) -> Result<(), TokenVestingError> {
+        if buffer.len() < Self::compute_allocation_size(0) {
+            return Err(TokenVestingError::DataTypeMismatch);
+        }
+        let (tag, buffer) = buffer.split_at_mut(8);
+        let tag: &mut u64 = bytemuck::from_bytes_mut(tag);
+        if *tag != super::Tag::Uninitialized as u64 {
+            return Err(TokenVestingError::DataTypeMismatch);
+        }
+        *tag = super::Tag::OwnerRegistry as u64;
+        let header: &mut OwnerRegistryHeader =
+            bytemuck::from_bytes_mut(&mut buffer[..OwnerRegistryHeader::LEN]);
+        header.owner = *owner;
+        Ok(())
+    }
+
+    /// Cast the buffer as an OwnerRegistry reference wrapper
+    pub fn from_buffer(buffer: &'a mut [u8]) -> Result<Self, TokenVestingError> {
+        if buffer.len() < Self::compute_allocation_size(0) {
+            return Err(TokenVestingError::DataTypeMismatch);
+        }
+        let (tag, buffer) = buffer.split_at_mut(8);
+        if *bytemuck::from_bytes_mut::<u64>(tag) != super::Tag::OwnerRegistry as u64 {
+            return Err(TokenVestingError::DataTypeMismatch);
+        }
+        let (header, buffer) = buffer.split_at_mut(OwnerRegistryHeader::LEN);
+        let contracts = bytemuck::try_cast_slice_mut(buffer)
+            .map_err(|_| TokenVestingError::DataTypeMismatch)?;
+        Ok(Self {
+            header: bytemuck::from_bytes_mut(header),
+            contracts,
+        })
+    }
+}
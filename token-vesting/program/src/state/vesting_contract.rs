@@ -1,12 +1,12 @@
-use bonfida_utils::WrappedPodMut;
 use bytemuck::{Pod, Zeroable};
 use solana_program::pubkey::Pubkey;
 
 use crate::error::TokenVestingError;
 
-#[derive(WrappedPodMut)]
 pub struct VestingContract<'a> {
     pub header: &'a mut VestingContractHeader,
+    /// Only present for accounts with a layout version of at least 1
+    pub extension: Option<&'a mut VestingContractExtension>,
     pub schedules: &'a mut [VestingSchedule],
 }
 
@@ -18,25 +18,409 @@ pub struct VestingContractHeader {
     pub owner: Pubkey,
     /// The contract escrow vault
     pub vault: Pubkey,
-    /// Index in the current schedule vector of the last completed schedule
+    /// Absolute index in the schedule vector of the first schedule which hasn't been claimed.
+    /// The contract is fully claimed once it reaches the number of schedules.
+    pub current_schedule_index: u64,
+    /// Used to generate the signing PDA which owns the vault
+    pub signer_nonce: u8,
+    /// The layout version of the account, zero for accounts created before versioning
+    pub version: u8,
+    /// The seeds used to derive the signing PDA, see [`VestingContractHeader::signer_seeds`]
+    pub signer_derivation: u8,
+    pub _padding: [u8; 5],
+}
+
+#[derive(Clone, Copy, Zeroable, Pod)]
+#[repr(C)]
+/// The header of the accounts created before the introduction of versioning. The current header
+/// has the same size and stores its new fields in what used to be zeroed out padding.
+pub struct LegacyVestingContractHeader {
+    /// The eventual token receiver
+    pub owner: Pubkey,
+    /// The contract escrow vault
+    pub vault: Pubkey,
+    /// Index in the schedule vector of the first schedule which hasn't been claimed
     pub current_schedule_index: u64,
     /// Used to generate the signing PDA which owns the vault
     pub signer_nonce: u8,
     pub _padding: [u8; 7],
 }
 
+#[derive(Clone, Copy, Zeroable, Pod)]
+#[repr(C)]
+/// Region following the header in versioned accounts. New fields are carved out of the
+/// reserved bytes so that the size of the region never changes.
+pub struct VestingContractExtension {
+    /// The account which funded the contract and is allowed to accelerate it, the default key
+    /// for migrated contracts
+    pub grantor: Pubkey,
+    /// The UTC timestamp of the last acceleration, zero if the contract was never accelerated
+    pub last_acceleration_timestamp: u64,
+    /// The number of times the grantor accelerated the contract
+    pub acceleration_count: u64,
+    /// The total quantity of tokens claimed by the owner so far, not counting the claims made
+    /// before the contract was migrated
+    pub claimed_amount: u64,
+    /// The mint of the vested tokens
+    pub mint: Pubkey,
+    /// The UTC timestamp of the creation of the contract, zero for migrated contracts
+    pub creation_timestamp: u64,
+    /// The total quantity of tokens vested by the contract, or the quantity left to claim at the
+    /// migration for migrated contracts
+    pub total_amount: u64,
+    /// The label and metadata URI set by the grantor
+    pub metadata: ContractMetadata,
+    /// The account allowed to activate the contract. When set, the unlock timestamps of the
+    /// schedules are offsets from the activation timestamp.
+    pub activator: Pubkey,
+    /// The UTC timestamp the contract was activated at, zero until then
+    pub activation_timestamp: u64,
+    /// The account which paid the rent of the contract and receives it back when it is closed,
+    /// left to the default key by migrations since the original payer isn't recorded
+    pub rent_payer: Pubkey,
+    /// The UTC timestamp after which the grantor can reclaim the vault, zero if the contract
+    /// never expires
+    pub expiry_timestamp: u64,
+    /// Set to one once the grantor reclaimed the vault of an expired contract
+    pub is_expired: u8,
+    /// Set to one while the freeze authority holds the releases of the contract
+    pub is_frozen: u8,
+    /// Set to one when the contract is counted in the [`MintStats`](super::mint_stats::MintStats)
+    /// of its mint, which isn't the case of migrated contracts
+    pub is_counted: u8,
+    /// Set to one when the contract is listed in the
+    /// [`OwnerRegistry`](super::owner_registry::OwnerRegistry) of its owner
+    pub is_registered: u8,
+    /// Set to one when the quantity of each schedule is the cumulative quantity unlocked up to
+    /// and including it, which lets claims find the unlocked schedules by binary search
+    pub is_cumulative: u8,
+    pub _padding: [u8; 3],
+    /// The accounts each release is divided between, registered by the owner. Only the entries
+    /// with a non-zero share are in use and they come first.
+    pub split_destinations: [SplitDestination; VestingContractExtension::MAX_SPLIT_DESTINATIONS],
+    /// The token account receiving the share of each release withheld by the grantor, the
+    /// default key if nothing is withheld
+    pub withholding_account: Pubkey,
+    /// The share of each release which is withheld, in basis points
+    pub withholding_bps: u64,
+    /// The account allowed to approve the milestones gating schedule entries, the default key if
+    /// no entry is gated
+    pub milestone_approver: Pubkey,
+    /// Bitmap of the schedule entries which are only released once their milestone is approved,
+    /// by index in the schedule vector
+    pub milestone_gated: MilestoneBitmap,
+    /// Bitmap of the milestone-gated schedule entries whose milestone was approved
+    pub milestone_approved: MilestoneBitmap,
+    /// The keys sharing the owner role, when the owner is an M-of-N set
+    pub owner_signers: SignerSet,
+    /// The keys sharing the grantor role, when the grantor is an M-of-N set
+    pub grantor_signers: SignerSet,
+    /// The account allowed to freeze the releases of the contract, the default key if it can't be
+    /// frozen
+    pub freeze_authority: Pubkey,
+    /// The UTC timestamp of the start of the last freeze, zero if the contract was never frozen
+    pub freeze_timestamp: u64,
+    /// The UTC timestamp of the end of the last freeze, zero if the contract was never thawed
+    pub thaw_timestamp: u64,
+    pub _reserved: [u8; 48],
+}
+
+#[derive(Clone, Copy, Zeroable, Pod, Debug, PartialEq, Eq)]
+#[repr(C)]
+/// A token account receiving a share of each release
+pub struct SplitDestination {
+    /// The token account receiving the share
+    pub token_account: Pubkey,
+    /// The share of each release, in basis points
+    pub bps: u64,
+}
+
+#[derive(Clone, Copy, Zeroable, Pod, Debug, PartialEq, Eq)]
+#[repr(C)]
+/// An M-of-N set of keys sharing a role of the contract. When the threshold isn't zero, the
+/// instructions of the role require the signatures of at least that many listed keys instead of
+/// the signature of the role key.
+pub struct SignerSet {
+    /// The number of listed keys which must sign, zero when the role has a single key
+    pub threshold: u64,
+    /// The listed keys, unused entries are the default key and come last
+    pub keys: [Pubkey; SignerSet::MAX_KEYS],
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+/// The roles of a vesting contract which can be held by an M-of-N set of keys
+pub enum Authority {
+    /// The eventual token receiver
+    Owner,
+    /// The account which funded the contract
+    Grantor,
+}
+
+#[derive(Clone, Copy, Zeroable, Pod, Debug, PartialEq, Eq)]
+#[repr(transparent)]
+/// A set of schedule entries, by index in the schedule vector. Only the first 256 entries can be
+/// part of the set.
+pub struct MilestoneBitmap(pub [u8; MilestoneBitmap::LEN]);
+
+#[derive(Clone, Copy, Zeroable, Pod)]
+#[repr(C)]
+/// A label and a metadata URI, both stored as zero-padded UTF-8 strings
+pub struct ContractMetadata {
+    /// A human readable label, for instance "Series A advisor grant"
+    pub label: [u8; 32],
+    /// A URI pointing to off-chain metadata, for instance the grant ID in an HR system
+    pub uri: [u8; 128],
+}
+
 #[derive(Clone, Copy, Zeroable, Pod)]
 #[repr(C)]
 /// An item of the vesting schedule
 pub struct VestingSchedule {
     /// When the unlock happens as a UTC timestamp
     pub unlock_timestamp: u64,
-    /// The quantity of tokens to unlock from the vault
+    /// The quantity of tokens to unlock from the vault, or the cumulative quantity unlocked up to
+    /// this schedule for contracts with the cumulative layout
     pub quantity: u64,
 }
 
 impl VestingContractHeader {
     pub const LEN: usize = std::mem::size_of::<Self>();
+    /// The layout of accounts which were created before the introduction of versioning
+    pub const LEGACY_VERSION: u8 = 0;
+    /// The layout of the accounts created by the current version of the program
+    pub const CURRENT_VERSION: u8 = 1;
+    /// The signer is derived from the contract key and any valid nonce
+    pub const LEGACY_SIGNER_DERIVATION: u8 = 0;
+    /// The signer is derived from [`VestingContract::SIGNER_SEED`], the contract key and the canonical bump
+    pub const NAMESPACED_SIGNER_DERIVATION: u8 = 1;
+
+    /// The seeds of the signing PDA which owns the vault
+    pub fn signer_seeds<'a>(&'a self, contract_key: &'a Pubkey) -> Vec<&'a [u8]> {
+        let nonce = std::slice::from_ref(&self.signer_nonce);
+        match self.signer_derivation {
+            Self::LEGACY_SIGNER_DERIVATION => vec![contract_key.as_ref(), nonce],
+            _ => vec![VestingContract::SIGNER_SEED, contract_key.as_ref(), nonce],
+        }
+    }
+}
+
+impl LegacyVestingContractHeader {
+    pub const LEN: usize = std::mem::size_of::<Self>();
+
+    /// Whether the header was written by the legacy program, which zeroed out the padding
+    pub fn is_legacy(&self) -> bool {
+        self._padding == [0; 7]
+    }
+
+    /// The header of the account once migrated to the current layout
+    pub fn upgrade(&self) -> VestingContractHeader {
+        VestingContractHeader {
+            owner: self.owner,
+            vault: self.vault,
+            current_schedule_index: self.current_schedule_index,
+            signer_nonce: self.signer_nonce,
+            version: VestingContractHeader::CURRENT_VERSION,
+            signer_derivation: VestingContractHeader::LEGACY_SIGNER_DERIVATION,
+            _padding: [0; 5],
+        }
+    }
+}
+
+impl VestingContractExtension {
+    pub const LEN: usize = std::mem::size_of::<Self>();
+    /// The maximum number of accounts a release can be divided between
+    pub const MAX_SPLIT_DESTINATIONS: usize = 4;
+
+    /// Whether the schedule entry at `index` is only released once its milestone is approved
+    pub fn is_milestone_gated(&self, index: usize) -> bool {
+        self.milestone_gated.contains(index)
+    }
+
+    /// Whether the milestone of the schedule entry at `index` was approved
+    pub fn is_milestone_approved(&self, index: usize) -> bool {
+        self.milestone_approved.contains(index)
+    }
+
+    /// Record the approval of the milestone of the schedule entry at `index`
+    pub fn approve_milestone(&mut self, index: usize) -> Result<(), TokenVestingError> {
+        if !self.is_milestone_gated(index) {
+            return Err(TokenVestingError::NotMilestoneGated);
+        }
+        if self.is_milestone_approved(index) {
+            return Err(TokenVestingError::MilestoneAlreadyApproved);
+        }
+        self.milestone_approved.insert(index);
+        Ok(())
+    }
+}
+
+impl MilestoneBitmap {
+    /// The size of the bitmap in bytes
+    pub const LEN: usize = 32;
+
+    /// Whether the entry at `index` is in the set, entries past the end of the bitmap never are
+    pub fn contains(&self, index: usize) -> bool {
+        match self.0.get(index / 8) {
+            Some(byte) => byte & (1 << (index % 8)) != 0,
+            None => false,
+        }
+    }
+
+    /// Add the entry at `index` to the set, which must be within the bitmap
+    pub fn insert(&mut self, index: usize) {
+        self.0[index / 8] |= 1 << (index % 8);
+    }
+
+    /// Whether the set is empty
+    pub fn is_empty(&self) -> bool {
+        self.0 == [0; Self::LEN]
+    }
+}
+
+impl SplitDestination {
+    /// The value designating a whole release
+    pub const TOTAL_BPS: u64 = 10_000;
+
+    /// Check that the destinations in use come first, are distinct and add up to a whole release.
+    /// Returns the number of destinations in use, which is zero when releases aren't divided.
+    pub fn check_split(destinations: &[SplitDestination]) -> Result<usize, TokenVestingError> {
+        let len = destinations.iter().take_while(|d| d.bps != 0).count();
+        let (used, unused) = destinations.split_at(len);
+        if unused.iter().any(|d| *d != SplitDestination::zeroed()) {
+            return Err(TokenVestingError::InvalidSplit);
+        }
+        let mut total_bps = 0u64;
+        for (i, d) in used.iter().enumerate() {
+            if d.token_account == Pubkey::default()
+                || used[..i].iter().any(|o| o.token_account == d.token_account)
+            {
+                return Err(TokenVestingError::InvalidSplit);
+            }
+            total_bps = total_bps
+                .checked_add(d.bps)
+                .ok_or(TokenVestingError::InvalidSplit)?;
+        }
+        if len != 0 && total_bps != Self::TOTAL_BPS {
+            return Err(TokenVestingError::InvalidSplit);
+        }
+        Ok(len)
+    }
+
+    /// Divide an amount between the destinations in use. Each destination but the first gets its
+    /// share rounded down, and the first one receives the remainder.
+    pub fn split_amount(destinations: &[SplitDestination], amount: u64) -> Vec<u64> {
+        let mut amounts = destinations
+            .iter()
+            .map(|d| ((amount as u128) * (d.bps as u128) / (Self::TOTAL_BPS as u128)) as u64)
+            .collect::<Vec<_>>();
+        if let Some(first) = amounts.first_mut() {
+            *first = 0;
+            let others: u64 = amounts.iter().sum();
+            amounts[0] = amount - others;
+        }
+        amounts
+    }
+}
+
+impl SignerSet {
+    pub const LEN: usize = std::mem::size_of::<Self>();
+    /// The maximum number of keys in a set
+    pub const MAX_KEYS: usize = 5;
+
+    /// Whether the role is held by the set rather than by a single key
+    pub fn is_enabled(&self) -> bool {
+        self.threshold != 0
+    }
+
+    /// Check that a set is either disabled and zeroed out, or lists distinct keys first and has
+    /// a threshold which they can meet
+    pub fn check(&self) -> Result<(), TokenVestingError> {
+        let len = self
+            .keys
+            .iter()
+            .take_while(|k| **k != Pubkey::default())
+            .count();
+        let (used, unused) = self.keys.split_at(len);
+        let has_duplicates = used.iter().enumerate().any(|(i, k)| used[..i].contains(k));
+        if unused.iter().any(|k| *k != Pubkey::default())
+            || has_duplicates
+            || self.threshold > len as u64
+            || (self.threshold == 0 && len != 0)
+        {
+            return Err(TokenVestingError::InvalidSignerSet);
+        }
+        Ok(())
+    }
+
+    /// The number of distinct listed keys among the signers
+    pub fn count_signatures<'a>(&self, signers: impl Iterator<Item = &'a Pubkey>) -> u64 {
+        let mut signed = [false; Self::MAX_KEYS];
+        for signer in signers {
+            if let Some(i) = self
+                .keys
+                .iter()
+                .position(|k| k == signer && *k != Pubkey::default())
+            {
+                signed[i] = true;
+            }
+        }
+        signed.iter().filter(|s| **s).count() as u64
+    }
+}
+
+impl ContractMetadata {
+    pub const LEN: usize = std::mem::size_of::<Self>();
+
+    /// Build the metadata from a label and a URI, which can be empty
+    pub fn new(label: &str, uri: &str) -> Result<Self, TokenVestingError> {
+        let mut metadata = Self::zeroed();
+        if label.len() > metadata.label.len() || uri.len() > metadata.uri.len() {
+            return Err(TokenVestingError::InvalidMetadata);
+        }
+        metadata.label[..label.len()].copy_from_slice(label.as_bytes());
+        metadata.uri[..uri.len()].copy_from_slice(uri.as_bytes());
+        Ok(metadata)
+    }
+
+    /// The label, empty if it isn't set
+    pub fn label(&self) -> Result<&str, TokenVestingError> {
+        parse_padded_str(&self.label)
+    }
+
+    /// The metadata URI, empty if it isn't set
+    pub fn uri(&self) -> Result<&str, TokenVestingError> {
+        parse_padded_str(&self.uri)
+    }
+
+    /// Check that both strings are valid UTF-8 followed only by zero padding
+    pub fn check(&self) -> Result<(), TokenVestingError> {
+        self.label()?;
+        self.uri()?;
+        Ok(())
+    }
+}
+
+fn parse_padded_str(bytes: &[u8]) -> Result<&str, TokenVestingError> {
+    let len = bytes.iter().position(|b| *b == 0).unwrap_or(bytes.len());
+    if bytes[len..].iter().any(|b| *b != 0) {
+        return Err(TokenVestingError::InvalidMetadata);
+    }
+    std::str::from_utf8(&bytes[..len]).map_err(|_| TokenVestingError::InvalidMetadata)
+}
+
+/// Offsets of the fields which can be used in `getProgramAccounts` memcmp filters
+impl VestingContract<'_> {
+    /// Offset of [`VestingContractHeader::owner`] in the account data
+    pub const OWNER_OFFSET: usize = 8;
+    /// Offset of [`VestingContractHeader::vault`] in the account data
+    pub const VAULT_OFFSET: usize = 40;
+    /// Offset of [`VestingContractHeader::version`] in the account data
+    pub const VERSION_OFFSET: usize = 81;
+    /// Offset of [`VestingContractExtension::grantor`] in the account data, for versioned accounts
+    pub const GRANTOR_OFFSET: usize = 8 + VestingContractHeader::LEN;
+    /// Offset of [`VestingContractExtension::mint`] in the account data, for versioned accounts.
+    /// It follows the grantor and the three acceleration and claim counters.
+    pub const MINT_OFFSET: usize = Self::GRANTOR_OFFSET + 32 + 3 * 8;
 }
 
 impl VestingSchedule {
@@ -44,18 +428,31 @@ impl VestingSchedule {
 }
 
 impl<'contract> VestingContract<'contract> {
-    /// Initialize a new VestingContract data account
+    /// The prefix of the seeds of the signing PDA which owns the vault
+    pub const SIGNER_SEED: &'static [u8] = b"vesting_contract_signer";
+
+    /// Find the signing PDA which owns the vault of a contract, along with its canonical bump
+    pub fn find_signer_address(contract_key: &Pubkey, program_id: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[Self::SIGNER_SEED, contract_key.as_ref()], program_id)
+    }
+
+    /// Initialize a new VestingContract data account with the current layout
     pub fn initialize(buffer: &mut [u8]) -> Result<(), TokenVestingError> {
-        let (tag, _) = buffer.split_at_mut(8);
+        let (tag, buffer) = buffer.split_at_mut(8);
         let tag: &mut u64 = bytemuck::from_bytes_mut(tag);
         if *tag != super::Tag::Uninitialized as u64 {
             return Err(TokenVestingError::DataTypeMismatch);
         }
         *tag = super::Tag::VestingContract as u64;
+        let header: &mut VestingContractHeader =
+            bytemuck::from_bytes_mut(&mut buffer[..VestingContractHeader::LEN]);
+        header.version = VestingContractHeader::CURRENT_VERSION;
         Ok(())
     }
 
     /// Cast the buffer asa a VestingContract reference wrapper
+    ///
+    /// Both the legacy and the current layouts are supported.
     pub fn from_buffer(
         buffer: &'contract mut [u8],
         expected_tag: super::Tag,
@@ -64,14 +461,371 @@ impl<'contract> VestingContract<'contract> {
         if *bytemuck::from_bytes_mut::<u64>(tag) != expected_tag as u64 {
             return Err(TokenVestingError::DataTypeMismatch);
         }
-        Ok(Self::from_bytes(buffer))
+        if buffer.len() < VestingContractHeader::LEN {
+            return Err(TokenVestingError::DataTypeMismatch);
+        }
+        let (header, buffer) = buffer.split_at_mut(VestingContractHeader::LEN);
+        // Legacy headers are read as current headers of version zero, which they are as long
+        // as their padding is zeroed out
+        let is_legacy = bytemuck::from_bytes::<LegacyVestingContractHeader>(header).is_legacy();
+        let header: &mut VestingContractHeader = bytemuck::from_bytes_mut(header);
+        let (extension, buffer) = if is_legacy {
+            (None, buffer)
+        } else if header.version == VestingContractHeader::CURRENT_VERSION {
+            if buffer.len() < VestingContractExtension::LEN {
+                return Err(TokenVestingError::DataTypeMismatch);
+            }
+            let (extension, buffer) = buffer.split_at_mut(VestingContractExtension::LEN);
+            (Some(bytemuck::from_bytes_mut(extension)), buffer)
+        } else {
+            return Err(TokenVestingError::DataTypeMismatch);
+        };
+        let schedules = bytemuck::try_cast_slice_mut(buffer)
+            .map_err(|_| TokenVestingError::DataTypeMismatch)?;
+        Ok(Self {
+            header,
+            extension,
+            schedules,
+        })
+    }
+
+    /// The extension of the contract, which legacy accounts need to be migrated to get
+    pub fn extension_mut(&mut self) -> Result<&mut VestingContractExtension, TokenVestingError> {
+        self.extension
+            .as_deref_mut()
+            .ok_or(TokenVestingError::LegacyLayout)
+    }
+
+    /// The grantor of the contract, which legacy accounts need to be migrated to get
+    pub fn grantor(&self) -> Result<&Pubkey, TokenVestingError> {
+        self.extension
+            .as_deref()
+            .map(|e| &e.grantor)
+            .ok_or(TokenVestingError::LegacyLayout)
+    }
+
+    /// The label of the contract, if any
+    pub fn label(&self) -> Option<&str> {
+        let label = self.extension.as_ref()?.metadata.label().ok()?;
+        if label.is_empty() {
+            None
+        } else {
+            Some(label)
+        }
+    }
+
+    /// The metadata URI of the contract, if any
+    pub fn uri(&self) -> Option<&str> {
+        let uri = self.extension.as_ref()?.metadata.uri().ok()?;
+        if uri.is_empty() {
+            None
+        } else {
+            Some(uri)
+        }
+    }
+
+    /// Whether the unlock timestamps are offsets from an activation timestamp set later on
+    pub fn has_deferred_activation(&self) -> bool {
+        self.extension
+            .as_ref()
+            .map(|e| e.activator != Pubkey::default())
+            .unwrap_or(false)
+    }
+
+    /// Whether the schedules of the contract can start unlocking
+    pub fn is_activated(&self) -> bool {
+        match &self.extension {
+            Some(e) if e.activator != Pubkey::default() => e.activation_timestamp != 0,
+            _ => true,
+        }
+    }
+
+    /// Convert a UTC timestamp to the time frame of the unlock timestamps of the schedules.
+    /// Returns `None` for contracts which haven't been activated at that time.
+    pub fn schedule_time(&self, timestamp: u64) -> Option<u64> {
+        match &self.extension {
+            Some(e) if e.activator != Pubkey::default() => {
+                if e.activation_timestamp == 0 {
+                    return None;
+                }
+                timestamp.checked_sub(e.activation_timestamp)
+            }
+            _ => Some(timestamp),
+        }
+    }
+
+    /// The UTC timestamp of an unlock, `None` if the contract hasn't been activated yet
+    pub fn unlock_timestamp(&self, schedule: &VestingSchedule) -> Option<u64> {
+        match &self.extension {
+            Some(e) if e.activator != Pubkey::default() => {
+                if e.activation_timestamp == 0 {
+                    return None;
+                }
+                e.activation_timestamp
+                    .checked_add(schedule.unlock_timestamp)
+            }
+            _ => Some(schedule.unlock_timestamp),
+        }
+    }
+
+    /// The destinations each release is divided between, empty when releases aren't divided
+    pub fn split_destinations(&self) -> &[SplitDestination] {
+        match &self.extension {
+            Some(e) => {
+                let len = e
+                    .split_destinations
+                    .iter()
+                    .take_while(|d| d.bps != 0)
+                    .count();
+                &e.split_destinations[..len]
+            }
+            None => &[],
+        }
+    }
+
+    /// The token account receiving the share of each release withheld by the grantor, if any
+    pub fn withholding_account(&self) -> Option<&Pubkey> {
+        match &self.extension {
+            Some(e) if e.withholding_bps != 0 => Some(&e.withholding_account),
+            _ => None,
+        }
+    }
+
+    /// Divide a release between the quantity withheld, rounded down, and the quantity the owner
+    /// receives
+    pub fn withhold(&self, amount: u64) -> (u64, u64) {
+        let bps = match &self.extension {
+            Some(e) => e.withholding_bps,
+            None => 0,
+        };
+        let withheld_amount =
+            ((amount as u128) * (bps as u128) / (SplitDestination::TOTAL_BPS as u128)) as u64;
+        (withheld_amount, amount - withheld_amount)
+    }
+
+    /// Whether the schedule entry at `index` is gated by a milestone which isn't approved yet
+    pub fn is_awaiting_milestone(&self, index: usize) -> bool {
+        match &self.extension {
+            Some(e) => e.is_milestone_gated(index) && !e.is_milestone_approved(index),
+            None => false,
+        }
+    }
+
+    /// The M-of-N set holding a role, `None` when the role has a single key
+    pub fn signer_set(&self, authority: Authority) -> Option<&SignerSet> {
+        let extension = self.extension.as_ref()?;
+        let signer_set = match authority {
+            Authority::Owner => &extension.owner_signers,
+            Authority::Grantor => &extension.grantor_signers,
+        };
+        if signer_set.is_enabled() {
+            Some(signer_set)
+        } else {
+            None
+        }
+    }
+
+    /// The number of token accounts a claim takes after the destination token account: the
+    /// withholding token account, if any, and the other split destinations
+    pub fn extra_destination_count(&self) -> usize {
+        let withholding_count = if self.withholding_account().is_some() {
+            1
+        } else {
+            0
+        };
+        withholding_count + self.split_destinations().len().saturating_sub(1)
+    }
+
+    /// Whether the releases of the contract are held by its freeze authority
+    pub fn is_frozen(&self) -> bool {
+        match &self.extension {
+            Some(e) => e.is_frozen != 0,
+            None => false,
+        }
+    }
+
+    /// Whether the contract is counted in the statistics of its mint
+    pub fn is_counted(&self) -> bool {
+        match &self.extension {
+            Some(e) => e.is_counted != 0,
+            None => false,
+        }
+    }
+
+    /// Whether the quantities of the schedules are cumulative
+    pub fn is_cumulative(&self) -> bool {
+        match &self.extension {
+            Some(e) => e.is_cumulative != 0,
+            None => false,
+        }
+    }
+
+    /// The quantity of tokens unlocked by the schedule at `index` alone, whatever the layout
+    pub fn schedule_quantity(&self, index: usize) -> u64 {
+        let quantity = self.schedules[index].quantity;
+        if self.is_cumulative() {
+            quantity - self.cumulative_quantity(index)
+        } else {
+            quantity
+        }
+    }
+
+    /// The cumulative quantity unlocked by the schedules before `index`, for contracts with the
+    /// cumulative layout
+    fn cumulative_quantity(&self, index: usize) -> u64 {
+        match index {
+            0 => 0,
+            _ => self.schedules[index - 1].quantity,
+        }
+    }
+
+    /// Turn the quantities of the schedules into cumulative quantities
+    pub fn make_cumulative(&mut self) -> Result<(), TokenVestingError> {
+        let mut total: u64 = 0;
+        for s in self.schedules.iter_mut() {
+            total = total
+                .checked_add(s.quantity)
+                .ok_or(TokenVestingError::Overflow)?;
+            s.quantity = total;
+        }
+        self.extension_mut()?.is_cumulative = 1;
+        Ok(())
+    }
+
+    /// Push back the remaining unlocks and the expiry, if any, by `duration` seconds. The unlocks
+    /// of a contract waiting for its activation haven't started yet and are left as is.
+    pub fn postpone(&mut self, duration: u64) -> Result<(), TokenVestingError> {
+        if self.is_activated() {
+            let index = self.first_unclaimed_index();
+            for s in self.schedules[index..].iter_mut() {
+                s.unlock_timestamp = s
+                    .unlock_timestamp
+                    .checked_add(duration)
+                    .ok_or(TokenVestingError::Overflow)?;
+            }
+        }
+        if let Some(e) = self.extension.as_deref_mut() {
+            if e.expiry_timestamp != 0 {
+                e.expiry_timestamp = e
+                    .expiry_timestamp
+                    .checked_add(duration)
+                    .ok_or(TokenVestingError::Overflow)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Whether the contract expired at `current_timestamp`, in which case nothing can be claimed
+    pub fn is_expired(&self, current_timestamp: u64) -> bool {
+        match &self.extension {
+            Some(e) => {
+                e.is_expired != 0
+                    || (e.expiry_timestamp != 0 && current_timestamp >= e.expiry_timestamp)
+            }
+            None => false,
+        }
+    }
+
+    /// The absolute index of the first schedule which hasn't been claimed
+    ///
+    /// Previous versions of the program could store an index relative to the previous one, or
+    /// `u64::MAX` once every schedule was claimed. A relative index is always lower than the
+    /// absolute one and only points to schedules which were zeroed out when claimed, while the
+    /// saturated value is clamped to the number of schedules.
+    pub fn first_unclaimed_index(&self) -> usize {
+        std::cmp::min(
+            self.header.current_schedule_index,
+            self.schedules.len() as u64,
+        ) as usize
+    }
+
+    /// Whether every schedule of the contract has been claimed
+    pub fn is_fully_claimed(&self) -> bool {
+        self.first_unclaimed_index() == self.schedules.len()
+    }
+
+    /// The quantity of tokens of the schedules which haven't been released yet
+    pub fn remaining_amount(&self) -> Result<u64, TokenVestingError> {
+        let index = self.first_unclaimed_index();
+        if self.is_cumulative() {
+            return Ok(
+                self.cumulative_quantity(self.schedules.len()) - self.cumulative_quantity(index)
+            );
+        }
+        self.schedules[index..]
+            .iter()
+            .try_fold(0u64, |total, s| total.checked_add(s.quantity))
+            .ok_or(TokenVestingError::Overflow)
+    }
+
+    /// Release the schedules which are unlocked at `current_timestamp` and move the schedule index
+    /// past them. Entries waiting for their milestone are skipped, and the schedule index stops at
+    /// the first one so that it is released once approved. Contracts with the cumulative layout
+    /// are released by binary search instead. Returns the total quantity released.
+    pub fn release_unlocked_schedules(
+        &mut self,
+        current_timestamp: u64,
+    ) -> Result<u64, TokenVestingError> {
+        // Contracts waiting for their activation are fully locked
+        let current_timestamp = match self.schedule_time(current_timestamp) {
+            Some(t) => t,
+            None => return Ok(0),
+        };
+        if self.is_cumulative() {
+            return Ok(self.release_cumulative_schedules(current_timestamp));
+        }
+        let start_index = self.first_unclaimed_index();
+        let mut index = start_index;
+        let mut is_blocked = false;
+        let mut total_amount: u64 = 0;
+        for i in start_index..self.schedules.len() {
+            if self.schedules[i].unlock_timestamp > current_timestamp {
+                break;
+            }
+            if self.is_awaiting_milestone(i) {
+                is_blocked = true;
+                continue;
+            }
+            let s = &mut self.schedules[i];
+            total_amount = total_amount
+                .checked_add(s.quantity)
+                .ok_or(TokenVestingError::Overflow)?;
+            // We zero out the schedule. Entries released past a pending milestone rely on
+            // this to never be released twice.
+            s.quantity = 0;
+            if !is_blocked {
+                index = i + 1;
+            }
+        }
+        // This prevents the same quantity from being unlocked twice
+        self.header.current_schedule_index = index as u64;
+        Ok(total_amount)
+    }
+
+    /// Release the unlocked schedules of a contract with the cumulative layout, which can't have
+    /// milestones. The remaining unlocks are sorted, so the unlocked ones are found by binary
+    /// search and released at once without writing to the schedules.
+    fn release_cumulative_schedules(&mut self, current_timestamp: u64) -> u64 {
+        let start_index = self.first_unclaimed_index();
+        let index = start_index
+            + self.schedules[start_index..]
+                .partition_point(|s| s.unlock_timestamp <= current_timestamp);
+        self.header.current_schedule_index = index as u64;
+        self.cumulative_quantity(index) - self.cumulative_quantity(start_index)
     }
 
     /// Compute a valid allocation size for a VestingContract
     pub fn compute_allocation_size(number_of_schedules: usize) -> usize {
+        Self::compute_legacy_allocation_size(number_of_schedules)
+            .checked_add(VestingContractExtension::LEN)
+            .unwrap()
+    }
+
+    /// Compute the allocation size of a VestingContract with the legacy layout
+    pub fn compute_legacy_allocation_size(number_of_schedules: usize) -> usize {
         number_of_schedules
             .checked_mul(VestingSchedule::LEN)
-            .and_then(|n| n.checked_add(VestingContractHeader::LEN))
+            .and_then(|n| n.checked_add(LegacyVestingContractHeader::LEN))
             .and_then(|n| n.checked_add(8))
             .unwrap()
     }
@@ -0,0 +1,265 @@
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use solana_program::pubkey::Pubkey;
+use token_vesting::{
+    error::TokenVestingError,
+    state::{
+        vesting_contract::{VestingContract, VestingSchedule},
+        Tag,
+    },
+};
+
+fn new_contract_buffer(schedules: &[VestingSchedule]) -> Vec<u8> {
+    let mut buffer = vec![0; VestingContract::compute_allocation_size(schedules.len())];
+    VestingContract::initialize(&mut buffer).unwrap();
+    let contract = VestingContract::from_buffer(&mut buffer, Tag::VestingContract).unwrap();
+    contract.schedules.copy_from_slice(schedules);
+    buffer
+}
+
+fn random_schedules(rng: &mut StdRng) -> Vec<VestingSchedule> {
+    let len = rng.gen_range(0..64);
+    let mut unlock_timestamp = 0;
+    (0..len)
+        .map(|_| {
+            // Several schedules can share the same timestamp
+            unlock_timestamp += rng.gen_range(0..10);
+            VestingSchedule {
+                unlock_timestamp,
+                quantity: rng.gen_range(0..1_000),
+            }
+        })
+        .collect()
+}
+
+#[test]
+fn test_no_schedule_is_claimed_twice() {
+    let mut rng = StdRng::seed_from_u64(42);
+    for _ in 0..1_000 {
+        let schedules = random_schedules(&mut rng);
+        let mut buffer = new_contract_buffer(&schedules);
+        let mut contract = VestingContract::from_buffer(&mut buffer, Tag::VestingContract).unwrap();
+
+        let mut claimed_per_schedule = vec![0u64; schedules.len()];
+        let mut current_timestamp = 0;
+        while !contract.is_fully_claimed() {
+            current_timestamp += rng.gen_range(0..30);
+            let start = contract.first_unclaimed_index();
+            let released = contract
+                .release_unlocked_schedules(current_timestamp)
+                .unwrap();
+            let end = contract.first_unclaimed_index();
+
+            // The index is absolute and only moves past unlocked schedules
+            assert_eq!(
+                end,
+                schedules
+                    .iter()
+                    .take_while(|s| s.unlock_timestamp <= current_timestamp)
+                    .count()
+                    .max(start)
+            );
+            assert_eq!(contract.header.current_schedule_index, end as u64);
+            assert_eq!(
+                released,
+                schedules[start..end]
+                    .iter()
+                    .map(|s| s.quantity)
+                    .sum::<u64>()
+            );
+            for c in claimed_per_schedule[start..end].iter_mut() {
+                *c += 1;
+            }
+        }
+
+        assert!(claimed_per_schedule.iter().all(|c| *c == 1));
+        assert_eq!(
+            contract.header.current_schedule_index,
+            schedules.len() as u64
+        );
+        // Claiming a fully claimed contract releases nothing
+        assert_eq!(contract.release_unlocked_schedules(u64::MAX).unwrap(), 0);
+    }
+}
+
+#[test]
+fn test_legacy_schedule_indices() {
+    let schedules = (1..=4)
+        .map(|i| VestingSchedule {
+            unlock_timestamp: i * 10,
+            quantity: i,
+        })
+        .collect::<Vec<_>>();
+
+    // Saturated index left by a previous version once every schedule was claimed
+    let mut buffer = new_contract_buffer(&schedules);
+    let mut contract = VestingContract::from_buffer(&mut buffer, Tag::VestingContract).unwrap();
+    contract.header.current_schedule_index = u64::MAX;
+    assert!(contract.is_fully_claimed());
+    assert_eq!(contract.release_unlocked_schedules(u64::MAX).unwrap(), 0);
+
+    // Relative index left by a previous version after claiming the first three schedules
+    // in two steps
+    let mut buffer = new_contract_buffer(&schedules);
+    let mut contract = VestingContract::from_buffer(&mut buffer, Tag::VestingContract).unwrap();
+    for s in contract.schedules[..3].iter_mut() {
+        s.quantity = 0;
+    }
+    contract.header.current_schedule_index = 1;
+    assert!(!contract.is_fully_claimed());
+    assert_eq!(contract.release_unlocked_schedules(40).unwrap(), 4);
+    assert!(contract.is_fully_claimed());
+}
+
+#[test]
+fn test_deferred_activation() {
+    let schedules = [
+        VestingSchedule {
+            unlock_timestamp: 0,
+            quantity: 10,
+        },
+        VestingSchedule {
+            unlock_timestamp: 100,
+            quantity: 20,
+        },
+    ];
+    let mut buffer = new_contract_buffer(&schedules);
+    let mut contract = VestingContract::from_buffer(&mut buffer, Tag::VestingContract).unwrap();
+    contract.extension_mut().unwrap().activator = Pubkey::new_unique();
+
+    // Nothing unlocks before the activation, whatever the current time
+    assert!(!contract.is_activated());
+    assert_eq!(contract.unlock_timestamp(&schedules[0]), None);
+    assert_eq!(contract.release_unlocked_schedules(u64::MAX).unwrap(), 0);
+
+    contract.extension_mut().unwrap().activation_timestamp = 1_000;
+    assert!(contract.is_activated());
+    assert_eq!(contract.unlock_timestamp(&schedules[1]), Some(1_100));
+    assert_eq!(contract.release_unlocked_schedules(999).unwrap(), 0);
+    assert_eq!(contract.release_unlocked_schedules(1_000).unwrap(), 10);
+    assert_eq!(contract.release_unlocked_schedules(1_099).unwrap(), 0);
+    assert_eq!(contract.release_unlocked_schedules(1_100).unwrap(), 20);
+    assert!(contract.is_fully_claimed());
+}
+
+#[test]
+fn test_milestone_gated_entries() {
+    let schedules = (0..3)
+        .map(|i| VestingSchedule {
+            unlock_timestamp: 100 * i,
+            quantity: 10 << i,
+        })
+        .collect::<Vec<_>>();
+    let mut buffer = new_contract_buffer(&schedules);
+    let mut contract = VestingContract::from_buffer(&mut buffer, Tag::VestingContract).unwrap();
+    let extension = contract.extension_mut().unwrap();
+    extension.milestone_approver = Pubkey::new_unique();
+    extension.milestone_gated.insert(1);
+
+    // The gated entry is skipped and holds the schedule index back
+    assert!(contract.is_awaiting_milestone(1));
+    assert_eq!(contract.release_unlocked_schedules(100).unwrap(), 10);
+    assert_eq!(contract.first_unclaimed_index(), 1);
+    assert_eq!(contract.release_unlocked_schedules(200).unwrap(), 40);
+    assert_eq!(contract.first_unclaimed_index(), 1);
+    assert_eq!(contract.release_unlocked_schedules(300).unwrap(), 0);
+
+    let extension = contract.extension_mut().unwrap();
+    assert_eq!(
+        extension.approve_milestone(0),
+        Err(TokenVestingError::NotMilestoneGated)
+    );
+    assert_eq!(
+        extension.approve_milestone(usize::MAX),
+        Err(TokenVestingError::NotMilestoneGated)
+    );
+    extension.approve_milestone(1).unwrap();
+    assert_eq!(
+        extension.approve_milestone(1),
+        Err(TokenVestingError::MilestoneAlreadyApproved)
+    );
+
+    // Once approved, only the gated entry is released
+    assert!(!contract.is_awaiting_milestone(1));
+    assert_eq!(contract.release_unlocked_schedules(300).unwrap(), 20);
+    assert!(contract.is_fully_claimed());
+}
+
+#[test]
+fn test_postpone() {
+    let schedules = (1..=3)
+        .map(|i| VestingSchedule {
+            unlock_timestamp: 100 * i,
+            quantity: 10,
+        })
+        .collect::<Vec<_>>();
+    let mut buffer = new_contract_buffer(&schedules);
+    let mut contract = VestingContract::from_buffer(&mut buffer, Tag::VestingContract).unwrap();
+    contract.extension_mut().unwrap().expiry_timestamp = 1_000;
+    assert_eq!(contract.release_unlocked_schedules(100).unwrap(), 10);
+
+    // Only the remaining unlocks are pushed back
+    contract.postpone(50).unwrap();
+    assert_eq!(
+        contract
+            .schedules
+            .iter()
+            .map(|s| s.unlock_timestamp)
+            .collect::<Vec<_>>(),
+        vec![100, 250, 350]
+    );
+    assert_eq!(contract.extension_mut().unwrap().expiry_timestamp, 1_050);
+    assert_eq!(contract.release_unlocked_schedules(249).unwrap(), 0);
+    assert_eq!(contract.release_unlocked_schedules(250).unwrap(), 10);
+
+    assert_eq!(
+        contract.postpone(u64::MAX),
+        Err(TokenVestingError::Overflow)
+    );
+
+    // The unlocks of a contract waiting for its activation haven't started yet
+    let mut buffer = new_contract_buffer(&schedules);
+    let mut contract = VestingContract::from_buffer(&mut buffer, Tag::VestingContract).unwrap();
+    contract.extension_mut().unwrap().activator = Pubkey::new_unique();
+    contract.postpone(50).unwrap();
+    assert_eq!(contract.schedules[0].unlock_timestamp, 100);
+}
+
+#[test]
+fn test_cumulative_layout() {
+    let mut rng = StdRng::seed_from_u64(42);
+    for _ in 0..1_000 {
+        let schedules = random_schedules(&mut rng);
+        let mut buffer = new_contract_buffer(&schedules);
+        let mut contract = VestingContract::from_buffer(&mut buffer, Tag::VestingContract).unwrap();
+        let mut cumulative_buffer = new_contract_buffer(&schedules);
+        let mut cumulative_contract =
+            VestingContract::from_buffer(&mut cumulative_buffer, Tag::VestingContract).unwrap();
+        cumulative_contract.make_cumulative().unwrap();
+        for (i, s) in schedules.iter().enumerate() {
+            assert_eq!(cumulative_contract.schedule_quantity(i), s.quantity);
+        }
+
+        // Both layouts release the same quantities at the same time
+        let mut current_timestamp = 0;
+        while !contract.is_fully_claimed() {
+            current_timestamp += rng.gen_range(0..30);
+            assert_eq!(
+                cumulative_contract
+                    .release_unlocked_schedules(current_timestamp)
+                    .unwrap(),
+                contract
+                    .release_unlocked_schedules(current_timestamp)
+                    .unwrap()
+            );
+            assert_eq!(
+                cumulative_contract.first_unclaimed_index(),
+                contract.first_unclaimed_index()
+            );
+            assert_eq!(
+                cumulative_contract.remaining_amount(),
+                contract.remaining_amount()
+            );
+        }
+        assert!(cumulative_contract.is_fully_claimed());
+    }
+}
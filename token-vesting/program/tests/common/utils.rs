@@ -1,13 +1,29 @@
 use std::str::FromStr;
 
+use bonfida_test_utils::{error::TestError, ProgramTestContextExt, ProgramTestExt};
+use bytemuck::Zeroable;
 use solana_program::instruction::Instruction;
 use solana_program::program_pack::Pack;
 use solana_program::pubkey::Pubkey;
-use solana_program_test::{BanksClientError, ProgramTest, ProgramTestContext};
+use solana_program::{system_instruction, system_program};
+use solana_program_test::{
+    processor, BanksClientError, ProgramTest, ProgramTestBanksClientExt, ProgramTestContext,
+};
 use solana_sdk::account::Account;
 use solana_sdk::signature::Signer;
 use solana_sdk::{signature::Keypair, transaction::Transaction};
+use spl_associated_token_account::get_associated_token_address;
 use spl_token::state::Mint;
+use token_vesting::{
+    entrypoint::process_instruction,
+    instruction::{create, get_claimable::VestingStatus, sweep_excess::SweepRecipient},
+    state::{
+        mint_stats::MintStats,
+        vesting_contract::{
+            ContractMetadata, MilestoneBitmap, SignerSet, VestingContract, VestingSchedule,
+        },
+    },
+};
 
 // Utils
 pub async fn sign_send_instructions(
@@ -54,3 +70,440 @@ pub fn mint_bootstrap(
     );
     (address, mint_info)
 }
+
+/// The test environment of the vesting program alone, to which tests can add programs before
+/// calling [`TestContext::start`]
+pub fn program_test() -> ProgramTest {
+    ProgramTest::new(
+        "token_vesting",
+        token_vesting::ID,
+        processor!(process_instruction),
+    )
+}
+
+/// A started test environment along with the mint of the vested tokens
+pub struct TestContext {
+    pub prg_test_ctx: ProgramTestContext,
+    pub mint_authority: Keypair,
+    pub mint: Pubkey,
+    /// The statistics account of the mint
+    pub mint_stats: Pubkey,
+}
+
+/// The accounts of a vesting contract which isn't created yet
+pub struct TestContract {
+    pub key: Pubkey,
+    pub vault_signer: Pubkey,
+    pub vault_signer_nonce: u8,
+    pub vault: Pubkey,
+}
+
+/// A grant whose contract is allocated but not created yet. The grantor holds the tokens of the
+/// schedule, and both parties have a token account of the mint.
+pub struct Grant {
+    pub grantor: Keypair,
+    pub owner: Keypair,
+    pub grantor_tokens: Pubkey,
+    pub owner_tokens: Pubkey,
+    pub contract: TestContract,
+    /// The creation of the contract with every option disabled, which tests adjust before
+    /// calling [`TestContext::create`]
+    pub create: CreateBuilder,
+}
+
+impl TestContext {
+    /// Start the vesting program with a new mint
+    pub async fn new() -> Self {
+        Self::start(program_test()).await
+    }
+
+    /// Start the vesting program with the native mint, whose tokens are wrapped SOL
+    pub async fn new_native() -> Self {
+        let mut program_test = program_test();
+        let mint_info = Mint {
+            decimals: 9,
+            is_initialized: true,
+            ..Mint::default()
+        };
+        let mut data = [0; Mint::LEN];
+        mint_info.pack_into_slice(&mut data);
+        program_test.add_account(
+            spl_token::native_mint::ID,
+            Account {
+                lamports: u32::MAX.into(),
+                data: data.into(),
+                owner: spl_token::ID,
+                ..Account::default()
+            },
+        );
+        let (mint_stats, _) =
+            MintStats::find_address(&spl_token::native_mint::ID, &token_vesting::ID);
+        Self {
+            prg_test_ctx: program_test.start_with_context().await,
+            mint_authority: Keypair::new(),
+            mint: spl_token::native_mint::ID,
+            mint_stats,
+        }
+    }
+
+    /// Start a test environment with a new mint
+    pub async fn start(mut program_test: ProgramTest) -> Self {
+        let mint_authority = Keypair::new();
+        let (mint, _) = program_test.add_mint(None, 6, &mint_authority.pubkey());
+        let (mint_stats, _) = MintStats::find_address(&mint, &token_vesting::ID);
+        Self {
+            prg_test_ctx: program_test.start_with_context().await,
+            mint_authority,
+            mint,
+            mint_stats,
+        }
+    }
+
+    /// Send the instructions in a transaction of their own. Each transaction gets a new blockhash,
+    /// so that sending the same instructions again isn't deduplicated.
+    pub async fn send(
+        &mut self,
+        instructions: &[Instruction],
+        signers: &[&Keypair],
+    ) -> Result<(), TestError> {
+        self.refresh_blockhash().await;
+        self.prg_test_ctx
+            .sign_send_instructions(instructions, signers)
+            .await
+    }
+
+    async fn refresh_blockhash(&mut self) {
+        self.prg_test_ctx.last_blockhash = self
+            .prg_test_ctx
+            .banks_client
+            .get_new_latest_blockhash(&self.prg_test_ctx.last_blockhash)
+            .await
+            .unwrap();
+    }
+
+    /// Create a token account of the mint for each owner
+    pub async fn token_accounts(&mut self, owners: &[Pubkey]) -> Vec<Pubkey> {
+        self.prg_test_ctx
+            .initialize_token_accounts(self.mint, owners)
+            .await
+            .unwrap()
+    }
+
+    pub async fn mint_to(&mut self, token_account: &Pubkey, amount: u64) {
+        self.refresh_blockhash().await;
+        // Wrapped SOL is minted by depositing lamports into the token account
+        if self.mint == spl_token::native_mint::ID {
+            let instructions = [
+                system_instruction::transfer(
+                    &self.prg_test_ctx.payer.pubkey(),
+                    token_account,
+                    amount,
+                ),
+                spl_token::instruction::sync_native(&spl_token::ID, token_account).unwrap(),
+            ];
+            return self
+                .prg_test_ctx
+                .sign_send_instructions(&instructions, &[])
+                .await
+                .unwrap();
+        }
+        self.prg_test_ctx
+            .mint_tokens(&self.mint_authority, &self.mint, token_account, amount)
+            .await
+            .unwrap()
+    }
+
+    pub async fn balance(&mut self, token_account: Pubkey) -> u64 {
+        self.prg_test_ctx
+            .get_token_account(token_account)
+            .await
+            .unwrap()
+            .amount
+    }
+
+    pub async fn lamports(&mut self, account: Pubkey) -> u64 {
+        self.prg_test_ctx
+            .banks_client
+            .get_balance(account)
+            .await
+            .unwrap()
+    }
+
+    pub async fn now(&mut self) -> u64 {
+        self.prg_test_ctx.get_current_timestamp().await.unwrap() as u64
+    }
+
+    pub async fn warp_to(&mut self, timestamp: u64) {
+        self.prg_test_ctx
+            .warp_to_timestamp(timestamp as i64)
+            .await
+            .unwrap()
+    }
+
+    /// Allocate the account of a contract with `number_of_schedules` schedules and its vault
+    pub async fn new_contract(&mut self, number_of_schedules: usize) -> TestContract {
+        let key = self
+            .prg_test_ctx
+            .initialize_new_account(
+                VestingContract::compute_allocation_size(number_of_schedules),
+                token_vesting::ID,
+            )
+            .await
+            .unwrap();
+        let (vault_signer, vault_signer_nonce) =
+            VestingContract::find_signer_address(&key, &token_vesting::ID);
+        let vault = self.token_accounts(&[vault_signer]).await[0];
+        TestContract {
+            key,
+            vault_signer,
+            vault_signer_nonce,
+            vault,
+        }
+    }
+
+    /// Prepare a grant of the schedule between two new parties
+    pub async fn grant(&mut self, schedule: &[VestingSchedule]) -> Grant {
+        let grantor = Keypair::new();
+        let owner = Keypair::new();
+        let token_accounts = self
+            .token_accounts(&[grantor.pubkey(), owner.pubkey()])
+            .await;
+        let total_amount = schedule.iter().map(|s| s.quantity).sum();
+        self.mint_to(&token_accounts[0], total_amount).await;
+        let contract = self.new_contract(schedule.len()).await;
+        let create = CreateBuilder {
+            vesting_contract: contract.key,
+            vault: contract.vault,
+            source_tokens: token_accounts[0],
+            source_tokens_owner: grantor.pubkey(),
+            recipient: owner.pubkey(),
+            payer: self.prg_test_ctx.payer.pubkey(),
+            mint_stats: self.mint_stats,
+            owner_registry: None,
+            withholding_token_account: None,
+            signer_nonce: contract.vault_signer_nonce as u64,
+            flags: 0,
+            metadata: ContractMetadata::zeroed(),
+            activator: Pubkey::default(),
+            expiry_timestamp: 0,
+            withholding_bps: 0,
+            milestone_approver: Pubkey::default(),
+            milestone_gated: MilestoneBitmap::zeroed(),
+            owner_signers: SignerSet::zeroed(),
+            grantor_signers: SignerSet::zeroed(),
+            freeze_authority: Pubkey::default(),
+            schedule: schedule.to_vec(),
+        };
+        Grant {
+            grantor,
+            owner,
+            grantor_tokens: token_accounts[0],
+            owner_tokens: token_accounts[1],
+            contract,
+            create,
+        }
+    }
+
+    /// Create the contract of a grant
+    pub async fn create(&mut self, grant: &Grant) -> Result<(), TestError> {
+        self.send(&[grant.create.instruction()], &[&grant.grantor])
+            .await
+    }
+
+    /// The claim of the unlocked schedules of a grant by its owner
+    pub fn claim(&self, grant: &Grant) -> Instruction {
+        token_vesting::instruction::claim(
+            token_vesting::instruction::claim::Accounts {
+                spl_token_program: &spl_token::ID,
+                vesting_contract: &grant.contract.key,
+                vesting_contract_signer: &grant.contract.vault_signer,
+                vault: &grant.contract.vault,
+                destination_token_account: &grant.owner_tokens,
+                owner: &grant.owner.pubkey(),
+                mint_stats: Some(&self.mint_stats),
+                extra_destination_token_accounts: &[],
+                co_signers: &[],
+            },
+            token_vesting::instruction::claim::Params {},
+        )
+    }
+
+    /// The claim of the unlocked schedules of a grant to the associated token account of its owner
+    pub fn claim_to_associated(&self, grant: &Grant, flags: u64) -> Instruction {
+        token_vesting::instruction::claim_to_associated(
+            token_vesting::instruction::claim_to_associated::Accounts {
+                spl_token_program: &spl_token::ID,
+                vesting_contract: &grant.contract.key,
+                vesting_contract_signer: &grant.contract.vault_signer,
+                vault: &grant.contract.vault,
+                destination_token_account: &get_associated_token_address(
+                    &grant.owner.pubkey(),
+                    &self.mint,
+                ),
+                owner: &grant.owner.pubkey(),
+                mint: &self.mint,
+                system_program: &system_program::ID,
+                associated_token_program: &spl_associated_token_account::ID,
+                payer: &self.prg_test_ctx.payer.pubkey(),
+                mint_stats: Some(&self.mint_stats),
+                extra_destination_token_accounts: &[],
+                co_signers: &[],
+            },
+            token_vesting::instruction::claim_to_associated::Params { flags },
+        )
+    }
+
+    /// The reclaim of the vault of an expired grant by its grantor
+    pub fn reclaim(&self, grant: &Grant) -> Instruction {
+        token_vesting::instruction::reclaim(
+            token_vesting::instruction::reclaim::Accounts {
+                spl_token_program: &spl_token::ID,
+                vesting_contract: &grant.contract.key,
+                vesting_contract_signer: &grant.contract.vault_signer,
+                vault: &grant.contract.vault,
+                destination_token_account: &grant.grantor_tokens,
+                grantor: &grant.grantor.pubkey(),
+                mint_stats: Some(&self.mint_stats),
+                co_signers: &[],
+            },
+            token_vesting::instruction::reclaim::Params {},
+        )
+    }
+
+    /// Evaluate a vesting contract with the `GetClaimable` instruction, whose result is read from
+    /// the return data of a simulation
+    pub async fn status(&mut self, vesting_contract: Pubkey) -> VestingStatus {
+        let ix = token_vesting::instruction::get_claimable(
+            token_vesting::instruction::get_claimable::Accounts {
+                vesting_contract: &vesting_contract,
+            },
+            token_vesting::instruction::get_claimable::Params {},
+        );
+        let transaction = Transaction::new_signed_with_payer(
+            &[ix],
+            Some(&self.prg_test_ctx.payer.pubkey()),
+            &[&self.prg_test_ctx.payer],
+            self.prg_test_ctx.last_blockhash,
+        );
+        let mut return_data = self
+            .prg_test_ctx
+            .banks_client
+            .simulate_transaction(transaction)
+            .await
+            .unwrap()
+            .simulation_details
+            .unwrap()
+            .return_data
+            .unwrap();
+        assert_eq!(return_data.program_id, token_vesting::ID);
+        // The trailing zeros of the return data are trimmed by the runtime
+        return_data.data.resize(VestingStatus::LEN, 0);
+        bytemuck::pod_read_unaligned(&return_data.data)
+    }
+
+    /// The sweep of the excess tokens of a grant by its grantor
+    pub fn sweep_excess(
+        &self,
+        grant: &Grant,
+        destination_token_account: &Pubkey,
+        recipient: SweepRecipient,
+    ) -> Instruction {
+        token_vesting::instruction::sweep_excess(
+            token_vesting::instruction::sweep_excess::Accounts {
+                spl_token_program: &spl_token::ID,
+                vesting_contract: &grant.contract.key,
+                vesting_contract_signer: &grant.contract.vault_signer,
+                vault: &grant.contract.vault,
+                destination_token_account,
+                grantor: &grant.grantor.pubkey(),
+                mint_stats: Some(&self.mint_stats),
+                co_signers: &[],
+            },
+            token_vesting::instruction::sweep_excess::Params {
+                recipient: recipient as u64,
+            },
+        )
+    }
+
+    /// Fetch the data of a vesting contract account
+    pub async fn contract_data(&mut self, vesting_contract: Pubkey) -> Vec<u8> {
+        self.prg_test_ctx
+            .banks_client
+            .get_account(vesting_contract)
+            .await
+            .unwrap()
+            .unwrap()
+            .data
+    }
+
+    /// Fetch the statistics of the mint
+    pub async fn mint_stats(&mut self) -> MintStats {
+        let account = self
+            .prg_test_ctx
+            .banks_client
+            .get_account(self.mint_stats)
+            .await
+            .unwrap()
+            .unwrap();
+        MintStats::read(&account.data).unwrap()
+    }
+}
+
+/// The accounts and params of a `Create` instruction
+pub struct CreateBuilder {
+    pub vesting_contract: Pubkey,
+    pub vault: Pubkey,
+    pub source_tokens: Pubkey,
+    pub source_tokens_owner: Pubkey,
+    pub recipient: Pubkey,
+    pub payer: Pubkey,
+    pub mint_stats: Pubkey,
+    pub owner_registry: Option<Pubkey>,
+    pub withholding_token_account: Option<Pubkey>,
+    pub signer_nonce: u64,
+    pub flags: u64,
+    pub metadata: ContractMetadata,
+    pub activator: Pubkey,
+    pub expiry_timestamp: u64,
+    pub withholding_bps: u64,
+    pub milestone_approver: Pubkey,
+    pub milestone_gated: MilestoneBitmap,
+    pub owner_signers: SignerSet,
+    pub grantor_signers: SignerSet,
+    pub freeze_authority: Pubkey,
+    pub schedule: Vec<VestingSchedule>,
+}
+
+impl CreateBuilder {
+    pub fn instruction(&self) -> Instruction {
+        token_vesting::instruction::create(
+            create::Accounts {
+                spl_token_program: &spl_token::ID,
+                vesting_contract: &self.vesting_contract,
+                vault: &self.vault,
+                source_tokens: &self.source_tokens,
+                source_tokens_owner: &self.source_tokens_owner,
+                recipient: &self.recipient,
+                system_program: &system_program::ID,
+                payer: &self.payer,
+                mint_stats: &self.mint_stats,
+                owner_registry: self.owner_registry.as_ref(),
+                withholding_token_account: self.withholding_token_account.as_ref(),
+            },
+            create::Params {
+                signer_nonce: &self.signer_nonce,
+                flags: &self.flags,
+                metadata: &self.metadata,
+                activator: &self.activator,
+                expiry_timestamp: &self.expiry_timestamp,
+                withholding_bps: &self.withholding_bps,
+                milestone_approver: &self.milestone_approver,
+                milestone_gated: &self.milestone_gated,
+                owner_signers: &self.owner_signers,
+                grantor_signers: &self.grantor_signers,
+                freeze_authority: &self.freeze_authority,
+                schedule: &self.schedule,
+            },
+        )
+    }
+}
@@ -0,0 +1,245 @@
+use bonfida_test_utils::ProgramTestContextExt;
+use bytemuck::Zeroable;
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    instruction::{AccountMeta, Instruction},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    system_program,
+};
+use token_vesting::{
+    cpi,
+    instruction::{claim, create, get_claimable},
+    state::vesting_contract::{
+        ContractMetadata, MilestoneBitmap, SignerSet, VestingContract, VestingSchedule,
+    },
+};
+
+use {solana_program_test::processor, solana_sdk::signer::Signer};
+
+use crate::common::utils::{program_test, TestContext};
+
+pub mod common;
+
+/// The seed of the PDA of the example treasury program which funds and receives the grants
+const AUTHORITY_SEED: &[u8] = b"treasury_authority";
+
+const CREATE: u8 = 0;
+const CLAIM: u8 = 1;
+const CHECK_CLAIMABLE: u8 = 2;
+
+/// An example program which creates, evaluates and claims vesting contracts through CPI
+fn treasury_process_instruction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let (_, authority_nonce) = Pubkey::find_program_address(&[AUTHORITY_SEED], program_id);
+    let authority_seeds: &[&[u8]] = &[AUTHORITY_SEED, &[authority_nonce]];
+
+    let accounts_iter = &mut accounts.iter();
+    let token_vesting_program = next_account_info(accounts_iter)?;
+
+    match instruction_data[0] {
+        CREATE => {
+            let accounts = create::Accounts {
+                spl_token_program: next_account_info(accounts_iter)?,
+                vesting_contract: next_account_info(accounts_iter)?,
+                vault: next_account_info(accounts_iter)?,
+                source_tokens: next_account_info(accounts_iter)?,
+                source_tokens_owner: next_account_info(accounts_iter)?,
+                recipient: next_account_info(accounts_iter)?,
+                system_program: next_account_info(accounts_iter)?,
+                payer: next_account_info(accounts_iter)?,
+                mint_stats: next_account_info(accounts_iter)?,
+                owner_registry: None,
+                withholding_token_account: None,
+            };
+            let (_, signer_nonce) = VestingContract::find_signer_address(
+                accounts.vesting_contract.key,
+                &token_vesting::ID,
+            );
+            let schedule = [bytemuck::pod_read_unaligned::<VestingSchedule>(
+                &instruction_data[1..],
+            )];
+            cpi::create(
+                token_vesting_program,
+                accounts,
+                create::Params {
+                    signer_nonce: &(signer_nonce as u64),
+                    flags: &0,
+                    metadata: &ContractMetadata::zeroed(),
+                    activator: &Pubkey::default(),
+                    expiry_timestamp: &0,
+                    withholding_bps: &0,
+                    milestone_approver: &Pubkey::default(),
+                    milestone_gated: &MilestoneBitmap::zeroed(),
+                    owner_signers: &SignerSet::zeroed(),
+                    grantor_signers: &SignerSet::zeroed(),
+                    freeze_authority: &Pubkey::default(),
+                    schedule: &schedule,
+                },
+                &[authority_seeds],
+            )
+        }
+        CLAIM => {
+            let accounts = claim::Accounts {
+                spl_token_program: next_account_info(accounts_iter)?,
+                vesting_contract: next_account_info(accounts_iter)?,
+                vesting_contract_signer: next_account_info(accounts_iter)?,
+                vault: next_account_info(accounts_iter)?,
+                destination_token_account: next_account_info(accounts_iter)?,
+                owner: next_account_info(accounts_iter)?,
+                mint_stats: Some(next_account_info(accounts_iter)?),
+                extra_destination_token_accounts: accounts_iter.as_slice(),
+                co_signers: &[],
+            };
+            cpi::claim(
+                token_vesting_program,
+                accounts,
+                claim::Params {},
+                &[authority_seeds],
+            )
+        }
+        CHECK_CLAIMABLE => {
+            let accounts = get_claimable::Accounts {
+                vesting_contract: next_account_info(accounts_iter)?,
+            };
+            let status = cpi::get_claimable(token_vesting_program, accounts)?;
+            let expected: [u64; 4] = bytemuck::pod_read_unaligned(&instruction_data[1..]);
+            let actual = [
+                status.claimable_amount,
+                status.locked_amount,
+                status.claimed_amount,
+                status.next_unlock_timestamp,
+            ];
+            // Each mismatching field fails with its own error code
+            match (0..4).find(|&i| actual[i] != expected[i]) {
+                Some(i) => Err(ProgramError::Custom(i as u32)),
+                None => Ok(()),
+            }
+        }
+        _ => Err(ProgramError::InvalidInstructionData),
+    }
+}
+
+#[tokio::test]
+async fn test_cpi() {
+    // Create program and test environment
+    const SECONDS_IN_HOUR: u64 = 3600;
+
+    let treasury_program_id = Pubkey::new_unique();
+    let (authority, _) = Pubkey::find_program_address(&[AUTHORITY_SEED], &treasury_program_id);
+
+    let mut program_test = program_test();
+    program_test.add_program(
+        "treasury",
+        treasury_program_id,
+        processor!(treasury_process_instruction),
+    );
+
+    ////
+    // Create test context
+    ////
+    let mut ctx = TestContext::start(program_test).await;
+
+    // The treasury account funds the grant and receives the vested tokens
+    let treasury_tokens = ctx.token_accounts(&[authority]).await[0];
+    ctx.mint_to(&treasury_tokens, 10_000_000).await;
+
+    let now = ctx.now().await;
+    let schedule = VestingSchedule {
+        unlock_timestamp: now + SECONDS_IN_HOUR,
+        quantity: 4_000_000,
+    };
+
+    let contract = ctx.new_contract(1).await;
+
+    // The treasury creates the contract
+    let mut data = vec![CREATE];
+    data.extend_from_slice(bytemuck::bytes_of(&schedule));
+    let ix = Instruction::new_with_bytes(
+        treasury_program_id,
+        &data,
+        vec![
+            AccountMeta::new_readonly(token_vesting::ID, false),
+            AccountMeta::new_readonly(spl_token::ID, false),
+            AccountMeta::new(contract.key, false),
+            AccountMeta::new(contract.vault, false),
+            AccountMeta::new(treasury_tokens, false),
+            AccountMeta::new_readonly(authority, false),
+            AccountMeta::new_readonly(authority, false),
+            AccountMeta::new_readonly(system_program::ID, false),
+            AccountMeta::new(ctx.prg_test_ctx.payer.pubkey(), true),
+            AccountMeta::new(ctx.mint_stats, false),
+        ],
+    );
+    ctx.prg_test_ctx
+        .sign_send_instructions(&[ix], &[])
+        .await
+        .unwrap();
+
+    // The expected claimable, locked and claimed amounts, and the next unlock timestamp
+    let check_claimable_ix = |expected: [u64; 4]| {
+        let mut data = vec![CHECK_CLAIMABLE];
+        data.extend_from_slice(bytemuck::bytes_of(&expected));
+        Instruction::new_with_bytes(
+            treasury_program_id,
+            &data,
+            vec![
+                AccountMeta::new_readonly(token_vesting::ID, false),
+                AccountMeta::new_readonly(contract.key, false),
+            ],
+        )
+    };
+    ctx.prg_test_ctx
+        .sign_send_instructions(
+            &[check_claimable_ix([
+                0,
+                schedule.quantity,
+                0,
+                schedule.unlock_timestamp,
+            ])],
+            &[],
+        )
+        .await
+        .unwrap();
+
+    ctx.prg_test_ctx
+        .warp_to_timestamp(schedule.unlock_timestamp as i64)
+        .await
+        .unwrap();
+
+    ctx.prg_test_ctx
+        .sign_send_instructions(&[check_claimable_ix([schedule.quantity, 0, 0, 0])], &[])
+        .await
+        .unwrap();
+
+    // The treasury claims the unlocked tokens back
+    let ix = Instruction::new_with_bytes(
+        treasury_program_id,
+        &[CLAIM],
+        vec![
+            AccountMeta::new_readonly(token_vesting::ID, false),
+            AccountMeta::new_readonly(spl_token::ID, false),
+            AccountMeta::new(contract.key, false),
+            AccountMeta::new_readonly(contract.vault_signer, false),
+            AccountMeta::new(contract.vault, false),
+            AccountMeta::new(treasury_tokens, false),
+            AccountMeta::new_readonly(authority, false),
+            AccountMeta::new(ctx.mint_stats, false),
+        ],
+    );
+    ctx.prg_test_ctx
+        .sign_send_instructions(&[ix], &[])
+        .await
+        .unwrap();
+
+    assert_eq!(ctx.balance(treasury_tokens).await, 10_000_000);
+
+    ctx.prg_test_ctx
+        .sign_send_instructions(&[check_claimable_ix([0, 0, schedule.quantity, 0])], &[])
+        .await
+        .unwrap();
+}
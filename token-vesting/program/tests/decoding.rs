@@ -0,0 +1,94 @@
+use solana_program::program_error::ProgramError;
+use token_vesting::{
+    entrypoint::process_instruction,
+    error::TokenVestingError,
+    instruction::ProgramInstruction,
+    state::vesting_contract::{ContractMetadata, SignerSet, VestingSchedule},
+};
+
+fn process(instruction_data: &[u8]) -> Result<(), ProgramError> {
+    process_instruction(&token_vesting::ID, &[], instruction_data)
+}
+
+fn tag(instruction: ProgramInstruction) -> Vec<u8> {
+    vec![instruction as u8, 0, 0, 0, 0, 0, 0, 0]
+}
+
+#[test]
+fn test_truncated_instruction_data() {
+    assert_eq!(
+        process(&[]),
+        Err(TokenVestingError::InvalidInstructionDataLength.into())
+    );
+    assert_eq!(
+        process(&[ProgramInstruction::Claim as u8]),
+        Err(TokenVestingError::InvalidInstructionDataLength.into())
+    );
+
+    // The accelerate parameters are 16 bytes long
+    let mut data = tag(ProgramInstruction::Accelerate);
+    data.extend_from_slice(&[0; 15]);
+    assert_eq!(
+        process(&data),
+        Err(TokenVestingError::InvalidInstructionDataLength.into())
+    );
+
+    // The create parameters need at least a signer nonce
+    let mut data = tag(ProgramInstruction::Create);
+    data.extend_from_slice(&[0; 4]);
+    assert_eq!(
+        process(&data),
+        Err(TokenVestingError::InvalidInstructionDataLength.into())
+    );
+}
+
+#[test]
+fn test_trailing_instruction_data() {
+    let mut data = tag(ProgramInstruction::Claim);
+    data.push(0);
+    assert_eq!(
+        process(&data),
+        Err(TokenVestingError::InvalidInstructionDataLength.into())
+    );
+
+    // A partial schedule is rejected
+    let mut data = tag(ProgramInstruction::Create);
+    data.extend_from_slice(
+        &[0; 16 + ContractMetadata::LEN + 32 + 16 + 64 + 2 * SignerSet::LEN + 32],
+    );
+    data.extend_from_slice(&[0; VestingSchedule::LEN + 1]);
+    assert_eq!(
+        process(&data),
+        Err(TokenVestingError::InvalidInstructionDataLength.into())
+    );
+}
+
+#[test]
+fn test_misaligned_instruction_data() {
+    // We shift the instruction data by one byte in an 8-byte aligned buffer
+    let mut buffer = vec![0u64; 4];
+    let bytes: &mut [u8] = bytemuck::cast_slice_mut(&mut buffer);
+    bytes[1] = ProgramInstruction::Accelerate as u8;
+    assert_eq!(
+        process(&bytes[1..25]),
+        Err(TokenVestingError::MisalignedInstructionData.into())
+    );
+}
+
+#[test]
+fn test_unknown_instruction() {
+    assert_eq!(
+        process(&[u8::MAX, 0, 0, 0, 0, 0, 0, 0]),
+        Err(ProgramError::InvalidInstructionData)
+    );
+}
+
+#[test]
+fn test_non_zero_tag_padding() {
+    // A valid instruction is rejected if any of the upper tag bytes is set
+    for i in 1..8 {
+        let mut data = tag(ProgramInstruction::Claim);
+        data[i] = 1;
+        assert_eq!(process(&data), Err(ProgramError::InvalidInstructionData));
+    }
+}
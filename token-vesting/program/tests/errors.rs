@@ -0,0 +1,91 @@
+use solana_program::program_error::ProgramError;
+use token_vesting::error::TokenVestingError;
+
+#[test]
+fn test_error_codes_are_stable() {
+    let codes = [
+        (TokenVestingError::AlreadyInitialized, 0),
+        (TokenVestingError::DataTypeMismatch, 1),
+        (TokenVestingError::WrongOwner, 2),
+        (TokenVestingError::Uninitialized, 3),
+        (TokenVestingError::InvalidVaultAccount, 4),
+        (TokenVestingError::WrongContractOwner, 5),
+        (TokenVestingError::WrongVault, 6),
+        (TokenVestingError::WrongContractSigner, 7),
+        (TokenVestingError::UnsortedSchedule, 8),
+        (TokenVestingError::WrongAccountSize, 9),
+        (TokenVestingError::Overflow, 10),
+        (TokenVestingError::NothingToClaim, 11),
+        (TokenVestingError::WrongGrantor, 12),
+        (TokenVestingError::InvalidAccelerationFraction, 13),
+        (TokenVestingError::NothingToAccelerate, 14),
+        (TokenVestingError::AccelerationDelaysUnlock, 15),
+        (TokenVestingError::AlreadyMigrated, 16),
+        (TokenVestingError::InvalidReturnData, 17),
+        (TokenVestingError::InvalidInstructionDataLength, 18),
+        (TokenVestingError::MisalignedInstructionData, 19),
+        (TokenVestingError::InvalidSignerNonce, 20),
+        (TokenVestingError::ContractFullyClaimed, 21),
+        (TokenVestingError::EmptySchedule, 22),
+        (TokenVestingError::ZeroQuantitySchedule, 23),
+        (TokenVestingError::UnlockInThePast, 24),
+        (TokenVestingError::InvalidRecipient, 25),
+        (TokenVestingError::MintMismatch, 26),
+        (TokenVestingError::InvalidCreateFlags, 28),
+        (TokenVestingError::NonCanonicalSignerNonce, 29),
+        (TokenVestingError::LegacyLayout, 30),
+        (TokenVestingError::InvalidMetadata, 31),
+        (TokenVestingError::NotActivated, 32),
+        (TokenVestingError::AlreadyActivated, 33),
+        (TokenVestingError::WrongActivator, 34),
+        (TokenVestingError::InvalidActivationTimestamp, 35),
+        (TokenVestingError::InvalidAccelerationTimestamp, 36),
+        (TokenVestingError::NoExcessTokens, 37),
+        (TokenVestingError::InvalidSweepRecipient, 38),
+        (TokenVestingError::WrongDestinationOwner, 39),
+        (TokenVestingError::InvalidAssociatedTokenAccount, 40),
+        (TokenVestingError::InvalidClaimFlags, 41),
+        (TokenVestingError::NotNativeMint, 42),
+        (TokenVestingError::NativeAccountNotEmpty, 43),
+        (TokenVestingError::InvalidExpiry, 44),
+        (TokenVestingError::NotExpirable, 45),
+        (TokenVestingError::NotExpired, 46),
+        (TokenVestingError::ContractExpired, 47),
+        (TokenVestingError::WrongSplitDestinations, 48),
+        (TokenVestingError::InvalidSplit, 49),
+        (TokenVestingError::InvalidWithholding, 50),
+        (TokenVestingError::WrongWithholdingAccount, 51),
+        (TokenVestingError::BonusOnWithheldContract, 52),
+        (TokenVestingError::InvalidMilestones, 53),
+        (TokenVestingError::WrongMilestoneApprover, 54),
+        (TokenVestingError::NotMilestoneGated, 55),
+        (TokenVestingError::MilestoneAlreadyApproved, 56),
+        (TokenVestingError::InvalidSignerSet, 57),
+        (TokenVestingError::NotEnoughSignatures, 58),
+        (TokenVestingError::SignerSetCannotUnwrap, 59),
+        (TokenVestingError::WrongFreezeAuthority, 60),
+        (TokenVestingError::ContractFrozen, 61),
+        (TokenVestingError::NotFrozen, 62),
+        (TokenVestingError::InvalidThawFlags, 63),
+        (TokenVestingError::WrongMintStats, 64),
+        (TokenVestingError::WrongOwnerRegistry, 65),
+    ];
+    for (error, code) in codes {
+        assert_eq!(
+            ProgramError::from(error.clone()),
+            ProgramError::Custom(code)
+        );
+        assert_eq!(TokenVestingError::from_code(code), Some(error.clone()));
+        assert_eq!(
+            TokenVestingError::from_program_error(&ProgramError::Custom(code)),
+            Some(error)
+        );
+    }
+    assert_eq!(
+        TokenVestingError::from_program_error(&ProgramError::InvalidArgument),
+        None
+    );
+
+    // Reserved codes of removed errors are never reused
+    assert_eq!(TokenVestingError::from_code(27), None);
+}
@@ -0,0 +1,66 @@
+use borsh::BorshSerialize;
+use solana_program::pubkey::Pubkey;
+use token_vesting::events::{ClaimEvent, Event, EVENT_VERSION};
+
+fn program_data(event: &Event) -> String {
+    let mut data = vec![EVENT_VERSION];
+    event.serialize(&mut data).unwrap();
+    format!("Program data: {}", base64::encode(data))
+}
+
+#[test]
+fn test_events_from_logs() {
+    let event = Event::Claim(ClaimEvent {
+        vesting_contract: Pubkey::new_unique(),
+        owner: Pubkey::new_unique(),
+        mint: Pubkey::new_unique(),
+        destination: Pubkey::new_unique(),
+        amount: 1_000_000,
+        withheld_amount: 0,
+        schedule_start_index: 0,
+        schedule_end_index: 2,
+        timestamp: 1_650_000_000,
+    });
+    let other_program = Pubkey::new_unique();
+
+    let logs = vec![
+        format!("Program {} invoke [1]", token_vesting::ID),
+        "Program log: Instruction: Claim".to_owned(),
+        format!("Program {} invoke [2]", spl_token::ID),
+        // Data logged by an invoked program must be ignored
+        program_data(&event),
+        format!("Program {} success", spl_token::ID),
+        program_data(&event),
+        format!("Program {} success", token_vesting::ID),
+        format!("Program {} invoke [1]", other_program),
+        program_data(&event),
+        format!("Program {} success", other_program),
+    ];
+
+    assert_eq!(Event::from_logs(&logs), vec![event]);
+}
+
+#[test]
+fn test_decode_rejects_unknown_version_and_trailing_bytes() {
+    let event = Event::Claim(ClaimEvent {
+        vesting_contract: Pubkey::new_unique(),
+        owner: Pubkey::new_unique(),
+        mint: Pubkey::new_unique(),
+        destination: Pubkey::new_unique(),
+        amount: 42,
+        withheld_amount: 8,
+        schedule_start_index: 1,
+        schedule_end_index: 3,
+        timestamp: 0,
+    });
+    let mut data = vec![EVENT_VERSION];
+    event.serialize(&mut data).unwrap();
+    assert_eq!(Event::decode(&data), Some(event));
+
+    let mut trailing = data.clone();
+    trailing.push(0);
+    assert_eq!(Event::decode(&trailing), None);
+
+    data[0] = EVENT_VERSION + 1;
+    assert_eq!(Event::decode(&data), None);
+}
@@ -1,17 +1,39 @@
 use bonfida_test_utils::{ProgramTestContextExt, ProgramTestExt};
-use solana_program::pubkey::Pubkey;
+use bytemuck::Zeroable;
+use solana_program::{
+    instruction::AccountMeta, program_pack::Pack, pubkey::Pubkey, rent::Rent, system_program,
+};
 use token_vesting::{
     entrypoint::process_instruction,
-    state::vesting_contract::{VestingContract, VestingSchedule},
+    instruction::{
+        claim_to_associated::ClaimFlag, create::CreateFlag, sweep_excess::SweepRecipient,
+        thaw::ThawFlag,
+    },
+    state::{
+        mint_stats::MintStats,
+        owner_registry::OwnerRegistry,
+        vesting_contract::{
+            ContractMetadata, MilestoneBitmap, SignerSet, SplitDestination, VestingContract,
+            VestingContractHeader, VestingSchedule,
+        },
+        Tag,
+    },
 };
 
 use {
     solana_program_test::{processor, ProgramTest},
-    solana_sdk::signer::{keypair::Keypair, Signer},
+    solana_sdk::{
+        account::Account,
+        signer::{keypair::Keypair, Signer},
+    },
 };
 
+use crate::common::utils::{TestContext, TestContract};
+
 pub mod common;
 
+const SECONDS_IN_HOUR: u64 = 3600;
+
 #[tokio::test]
 async fn test_01() {
     // Create program and test environment
@@ -30,6 +52,7 @@ async fn test_01() {
     );
 
     let (mint_key, _) = program_test.add_mint(None, 6, &keypairs[MINT_AUTHORITY].pubkey());
+    let (mint_stats, _) = MintStats::find_address(&mint_key, &token_vesting::ID);
 
     ////
     // Create test context
@@ -89,7 +112,7 @@ async fn test_01() {
         .unwrap();
 
     let (vault_signer, vault_signer_nonce) =
-        Pubkey::find_program_address(&[&vesting_contract.to_bytes()], &token_vesting::ID);
+        VestingContract::find_signer_address(&vesting_contract, &token_vesting::ID);
     let vault = prg_test_ctx
         .initialize_token_accounts(mint_key, &[vault_signer])
         .await
@@ -104,9 +127,24 @@ async fn test_01() {
             source_tokens: &ata_keys[ALICE],
             source_tokens_owner: &keypairs[ALICE].pubkey(),
             recipient: &keypairs[BOB].pubkey(),
+            system_program: &system_program::ID,
+            payer: &prg_test_ctx.payer.pubkey(),
+            mint_stats: &mint_stats,
+            owner_registry: None,
+            withholding_token_account: None,
         },
         token_vesting::instruction::create::Params {
             signer_nonce: &(vault_signer_nonce as u64),
+            flags: &0,
+            metadata: &ContractMetadata::zeroed(),
+            activator: &Pubkey::default(),
+            expiry_timestamp: &0,
+            withholding_bps: &0,
+            milestone_approver: &Pubkey::default(),
+            milestone_gated: &MilestoneBitmap::zeroed(),
+            owner_signers: &SignerSet::zeroed(),
+            grantor_signers: &SignerSet::zeroed(),
+            freeze_authority: &Pubkey::default(),
             schedule: &schedule,
         },
     );
@@ -144,6 +182,9 @@ async fn test_01() {
                 vault: &vault,
                 destination_token_account: &ata_keys[BOB],
                 owner: &keypairs[BOB].pubkey(),
+                mint_stats: Some(&mint_stats),
+                extra_destination_token_accounts: &[],
+                co_signers: &[],
             },
             token_vesting::instruction::claim::Params {},
         );
@@ -162,3 +203,1044 @@ async fn test_01() {
         assert_eq!(bob_token_account_balance - previous_balance, v.quantity);
     }
 }
+
+#[tokio::test]
+async fn test_accelerate() {
+    let mut ctx = TestContext::new().await;
+    let now = ctx.now().await;
+    let schedule = (1..=4)
+        .map(|i| VestingSchedule {
+            unlock_timestamp: now + i * SECONDS_IN_HOUR,
+            quantity: 1_000_000,
+        })
+        .collect::<Vec<_>>();
+    let grant = ctx.grant(&schedule).await;
+    ctx.create(&grant).await.unwrap();
+
+    let accelerate_ix = |grantor: &Keypair, unlock_timestamp: u64, fraction_bps: u64| {
+        token_vesting::instruction::accelerate(
+            token_vesting::instruction::accelerate::Accounts {
+                vesting_contract: &grant.contract.key,
+                grantor: &grantor.pubkey(),
+                co_signers: &[],
+            },
+            token_vesting::instruction::accelerate::Params {
+                unlock_timestamp,
+                fraction_bps,
+            },
+        )
+    };
+
+    // The owner cannot accelerate their own contract
+    assert!(ctx
+        .send(&[accelerate_ix(&grant.owner, now, 10_000)], &[&grant.owner])
+        .await
+        .is_err());
+
+    // The grantor cannot delay an unlock
+    assert!(ctx
+        .send(
+            &[accelerate_ix(
+                &grant.grantor,
+                now + 2 * SECONDS_IN_HOUR,
+                5_000
+            )],
+            &[&grant.grantor]
+        )
+        .await
+        .is_err());
+
+    // The grantor accelerates half of the schedules to the current time
+    ctx.send(
+        &[accelerate_ix(&grant.grantor, now, 5_000)],
+        &[&grant.grantor],
+    )
+    .await
+    .unwrap();
+
+    ctx.send(&[ctx.claim(&grant)], &[&grant.owner])
+        .await
+        .unwrap();
+    assert_eq!(ctx.balance(grant.owner_tokens).await, 2_000_000);
+
+    // The acceleration is recorded in the contract
+    let mut data = ctx.contract_data(grant.contract.key).await;
+    let vesting_contract = VestingContract::from_buffer(&mut data, Tag::VestingContract).unwrap();
+    let extension = vesting_contract.extension.as_ref().unwrap();
+    assert_eq!(extension.grantor, grant.grantor.pubkey());
+    assert_eq!(extension.mint, ctx.mint);
+    assert_eq!(extension.creation_timestamp, now);
+    assert_eq!(extension.total_amount, 4_000_000);
+    assert_eq!(extension.rent_payer, ctx.prg_test_ctx.payer.pubkey());
+    assert_eq!(extension.acceleration_count, 1);
+    assert_eq!(extension.claimed_amount, 2_000_000);
+}
+
+#[tokio::test]
+async fn test_get_claimable() {
+    let mut ctx = TestContext::new().await;
+    let now = ctx.now().await;
+    let schedule = (1..=2)
+        .map(|i| VestingSchedule {
+            unlock_timestamp: now + i * SECONDS_IN_HOUR,
+            quantity: i * 1_000_000,
+        })
+        .collect::<Vec<_>>();
+    let grant = ctx.grant(&schedule).await;
+    ctx.create(&grant).await.unwrap();
+
+    let status = ctx.status(grant.contract.key).await;
+    assert_eq!(status.claimable_amount, 0);
+    assert_eq!(status.locked_amount, 3_000_000);
+    assert_eq!(status.claimed_amount, 0);
+    assert_eq!(status.next_unlock_timestamp, schedule[0].unlock_timestamp);
+
+    // The first entry is claimable at its unlock
+    ctx.warp_to(schedule[0].unlock_timestamp).await;
+    let status = ctx.status(grant.contract.key).await;
+    assert_eq!(status.claimable_amount, 1_000_000);
+    assert_eq!(status.locked_amount, 2_000_000);
+    assert_eq!(status.next_unlock_timestamp, schedule[1].unlock_timestamp);
+
+    // Once it is claimed, only the second entry remains
+    ctx.send(&[ctx.claim(&grant)], &[&grant.owner])
+        .await
+        .unwrap();
+    let status = ctx.status(grant.contract.key).await;
+    assert_eq!(status.claimable_amount, 0);
+    assert_eq!(status.locked_amount, 2_000_000);
+    assert_eq!(status.claimed_amount, 1_000_000);
+    assert_eq!(status.next_unlock_timestamp, schedule[1].unlock_timestamp);
+}
+
+#[tokio::test]
+async fn test_create_past_unlocks() {
+    let mut ctx = TestContext::new().await;
+    let now = ctx.now().await;
+    let mut grant = ctx
+        .grant(&[VestingSchedule {
+            unlock_timestamp: now - SECONDS_IN_HOUR,
+            quantity: 1_000_000,
+        }])
+        .await;
+
+    // Back-dated grants need to be allowed explicitly
+    assert!(ctx.create(&grant).await.is_err());
+
+    grant.create.flags = CreateFlag::AllowPastUnlocks as u64;
+    ctx.create(&grant).await.unwrap();
+    ctx.send(&[ctx.claim(&grant)], &[&grant.owner])
+        .await
+        .unwrap();
+    assert_eq!(ctx.balance(grant.owner_tokens).await, 1_000_000);
+}
+
+#[tokio::test]
+async fn test_migrate() {
+    let owner = Keypair::new();
+
+    let mut ctx = TestContext::new().await;
+
+    // The legacy program derived the vault signer from the contract key alone
+    let vesting_contract = Pubkey::new_unique();
+    let (vault_signer, vault_signer_nonce) =
+        Pubkey::find_program_address(&[&vesting_contract.to_bytes()], &token_vesting::ID);
+    let vault = ctx.token_accounts(&[vault_signer]).await[0];
+    ctx.mint_to(&vault, 15_000_000).await;
+    let contract = TestContract {
+        key: vesting_contract,
+        vault_signer,
+        vault_signer_nonce,
+        vault,
+    };
+
+    let now = ctx.now().await;
+    let schedule = [
+        VestingSchedule {
+            unlock_timestamp: now + SECONDS_IN_HOUR,
+            quantity: 10_000_000,
+        },
+        VestingSchedule {
+            unlock_timestamp: now + 2 * SECONDS_IN_HOUR,
+            quantity: 5_000_000,
+        },
+    ];
+
+    // The bytes are laid out the way the legacy program wrote them, with an 80-byte header
+    let mut data = (Tag::VestingContract as u64).to_le_bytes().to_vec();
+    data.extend_from_slice(&owner.pubkey().to_bytes());
+    data.extend_from_slice(&vault.to_bytes());
+    data.extend_from_slice(&0u64.to_le_bytes());
+    data.push(vault_signer_nonce);
+    data.extend_from_slice(&[0; 7]);
+    for s in schedule.iter() {
+        data.extend_from_slice(&s.unlock_timestamp.to_le_bytes());
+        data.extend_from_slice(&s.quantity.to_le_bytes());
+    }
+    assert_eq!(
+        data.len(),
+        VestingContract::compute_legacy_allocation_size(schedule.len())
+    );
+    ctx.prg_test_ctx.set_account(
+        &vesting_contract,
+        &Account {
+            lamports: Rent::default().minimum_balance(data.len()),
+            data,
+            owner: token_vesting::ID,
+            ..Account::default()
+        }
+        .into(),
+    );
+
+    let owner_tokens = ctx.token_accounts(&[owner.pubkey()]).await[0];
+    let claim_ix = || {
+        token_vesting::instruction::claim(
+            token_vesting::instruction::claim::Accounts {
+                spl_token_program: &spl_token::ID,
+                vesting_contract: &contract.key,
+                vesting_contract_signer: &contract.vault_signer,
+                vault: &contract.vault,
+                destination_token_account: &owner_tokens,
+                owner: &owner.pubkey(),
+                // Legacy and migrated contracts aren't counted in the statistics of their mint
+                mint_stats: None,
+                extra_destination_token_accounts: &[],
+                co_signers: &[],
+            },
+            token_vesting::instruction::claim::Params {},
+        )
+    };
+
+    // The legacy contract can be claimed before being migrated
+    ctx.warp_to(schedule[0].unlock_timestamp).await;
+    ctx.send(&[claim_ix()], &[&owner]).await.unwrap();
+
+    let payer = ctx.prg_test_ctx.payer.pubkey();
+    let migrate_ix = || {
+        token_vesting::instruction::migrate(
+            token_vesting::instruction::migrate::Accounts {
+                system_program: &system_program::ID,
+                vesting_contract: &vesting_contract,
+                vault: &vault,
+                payer: &payer,
+            },
+            token_vesting::instruction::migrate::Params {},
+        )
+    };
+    ctx.send(&[migrate_ix()], &[]).await.unwrap();
+
+    let mut data = ctx.contract_data(vesting_contract).await;
+    assert_eq!(
+        data.len(),
+        VestingContract::compute_allocation_size(schedule.len())
+    );
+    let migrated = VestingContract::from_buffer(&mut data, Tag::VestingContract).unwrap();
+    assert_eq!(
+        migrated.header.version,
+        VestingContractHeader::CURRENT_VERSION
+    );
+    assert_eq!(migrated.header.owner, owner.pubkey());
+    assert_eq!(migrated.header.signer_nonce, vault_signer_nonce);
+    assert_eq!(
+        migrated.header.signer_derivation,
+        VestingContractHeader::LEGACY_SIGNER_DERIVATION
+    );
+    let extension = migrated.extension.as_ref().unwrap();
+    assert_eq!(extension.grantor, Pubkey::default());
+    assert_eq!(extension.rent_payer, Pubkey::default());
+    assert_eq!(extension.claimed_amount, 0);
+    assert_eq!(extension.mint, ctx.mint);
+    assert_eq!(extension.creation_timestamp, 0);
+    assert_eq!(extension.total_amount, 5_000_000);
+    assert_eq!(migrated.schedules[1].quantity, schedule[1].quantity);
+
+    // Contracts can only be migrated once
+    assert!(ctx.send(&[migrate_ix()], &[]).await.is_err());
+
+    // The migrated contract can still be claimed
+    ctx.warp_to(schedule[1].unlock_timestamp).await;
+    ctx.send(&[claim_ix()], &[&owner]).await.unwrap();
+    assert_eq!(ctx.balance(owner_tokens).await, 15_000_000);
+}
+
+#[tokio::test]
+async fn test_set_metadata() {
+    let mut ctx = TestContext::new().await;
+    let now = ctx.now().await;
+    let mut grant = ctx
+        .grant(&[VestingSchedule {
+            unlock_timestamp: now + SECONDS_IN_HOUR,
+            quantity: 1_000_000,
+        }])
+        .await;
+
+    // The label is set at creation
+    grant.create.metadata = ContractMetadata::new("Advisor grant", "").unwrap();
+    ctx.create(&grant).await.unwrap();
+
+    let mut data = ctx.contract_data(grant.contract.key).await;
+    let vesting_contract = VestingContract::from_buffer(&mut data, Tag::VestingContract).unwrap();
+    assert_eq!(vesting_contract.label(), Some("Advisor grant"));
+    assert_eq!(vesting_contract.uri(), None);
+
+    let set_metadata_ix = |grantor: &Pubkey| {
+        token_vesting::instruction::set_metadata(
+            token_vesting::instruction::set_metadata::Accounts {
+                vesting_contract: &grant.contract.key,
+                grantor,
+                co_signers: &[],
+            },
+            token_vesting::instruction::set_metadata::Params {
+                metadata: ContractMetadata::new("Advisor grant", "https://example.com/grants/42")
+                    .unwrap(),
+            },
+        )
+    };
+
+    // Only the grantor can update the metadata
+    assert!(ctx
+        .send(&[set_metadata_ix(&grant.owner.pubkey())], &[&grant.owner])
+        .await
+        .is_err());
+    ctx.send(
+        &[set_metadata_ix(&grant.grantor.pubkey())],
+        &[&grant.grantor],
+    )
+    .await
+    .unwrap();
+
+    let mut data = ctx.contract_data(grant.contract.key).await;
+    let vesting_contract = VestingContract::from_buffer(&mut data, Tag::VestingContract).unwrap();
+    assert_eq!(vesting_contract.label(), Some("Advisor grant"));
+    assert_eq!(
+        vesting_contract.uri(),
+        Some("https://example.com/grants/42")
+    );
+}
+
+#[tokio::test]
+async fn test_activate() {
+    let activator = Keypair::new();
+
+    let mut ctx = TestContext::new().await;
+
+    // The unlocks are offsets from the activation
+    let schedule = (0..2)
+        .map(|i| VestingSchedule {
+            unlock_timestamp: i * SECONDS_IN_HOUR,
+            quantity: 1_000_000,
+        })
+        .collect::<Vec<_>>();
+    let mut grant = ctx.grant(&schedule).await;
+
+    // The contract expires ten hours from now
+    let now = ctx.now().await;
+    let expiry_timestamp = now + 10 * SECONDS_IN_HOUR;
+
+    grant.create.activator = activator.pubkey();
+    grant.create.expiry_timestamp = expiry_timestamp;
+    ctx.create(&grant).await.unwrap();
+
+    // The contract is fully locked until it is activated
+    assert!(ctx
+        .send(&[ctx.claim(&grant)], &[&grant.owner])
+        .await
+        .is_err());
+
+    let activate_ix = |activator: &Keypair, activation_timestamp: u64| {
+        token_vesting::instruction::activate(
+            token_vesting::instruction::activate::Accounts {
+                vesting_contract: &grant.contract.key,
+                activator: &activator.pubkey(),
+            },
+            token_vesting::instruction::activate::Params {
+                activation_timestamp,
+            },
+        )
+    };
+    let accelerate_ix = |unlock_timestamp: u64| {
+        token_vesting::instruction::accelerate(
+            token_vesting::instruction::accelerate::Accounts {
+                vesting_contract: &grant.contract.key,
+                grantor: &grant.grantor.pubkey(),
+                co_signers: &[],
+            },
+            token_vesting::instruction::accelerate::Params {
+                unlock_timestamp,
+                fraction_bps: 10_000,
+            },
+        )
+    };
+
+    // Unlocks cannot be accelerated before the activation sets their timestamps
+    assert!(ctx
+        .send(&[accelerate_ix(now)], &[&grant.grantor])
+        .await
+        .is_err());
+
+    // Only the activator can activate the contract
+    assert!(ctx
+        .send(&[activate_ix(&grant.grantor, now)], &[&grant.grantor])
+        .await
+        .is_err());
+
+    // The last unlock cannot fall at or after the expiry
+    assert!(ctx
+        .send(
+            &[activate_ix(&activator, expiry_timestamp - SECONDS_IN_HOUR)],
+            &[&activator],
+        )
+        .await
+        .is_err());
+
+    ctx.send(&[activate_ix(&activator, now)], &[&activator])
+        .await
+        .unwrap();
+
+    // The activation can only happen once
+    assert!(ctx
+        .send(&[activate_ix(&activator, now + 1)], &[&activator])
+        .await
+        .is_err());
+
+    // Unlocks cannot be moved before the activation
+    assert!(ctx
+        .send(&[accelerate_ix(now - 1)], &[&grant.grantor])
+        .await
+        .is_err());
+
+    // The first schedule unlocks at the activation
+    ctx.send(&[ctx.claim(&grant)], &[&grant.owner])
+        .await
+        .unwrap();
+    assert_eq!(ctx.balance(grant.owner_tokens).await, 1_000_000);
+}
+
+#[tokio::test]
+async fn test_sweep_excess() {
+    let mut ctx = TestContext::new().await;
+    let now = ctx.now().await;
+    let grant = ctx
+        .grant(&[VestingSchedule {
+            unlock_timestamp: now + SECONDS_IN_HOUR,
+            quantity: 1_000_000,
+        }])
+        .await;
+    ctx.create(&grant).await.unwrap();
+
+    // Tokens are sent to the vault by mistake
+    ctx.mint_to(&grant.contract.vault, 300_000).await;
+
+    // The excess of the grantor is only returned to their own account
+    let ix = ctx.sweep_excess(&grant, &grant.owner_tokens, SweepRecipient::Grantor);
+    assert!(ctx.send(&[ix], &[&grant.grantor]).await.is_err());
+
+    let ix = ctx.sweep_excess(&grant, &grant.grantor_tokens, SweepRecipient::Grantor);
+    ctx.send(&[ix], &[&grant.grantor]).await.unwrap();
+    assert_eq!(ctx.balance(grant.grantor_tokens).await, 300_000);
+
+    // The scheduled tokens are never swept
+    let ix = ctx.sweep_excess(&grant, &grant.grantor_tokens, SweepRecipient::Grantor);
+    assert!(ctx.send(&[ix], &[&grant.grantor]).await.is_err());
+
+    // The excess can also be granted to the owner as a vested bonus
+    ctx.mint_to(&grant.contract.vault, 200_000).await;
+    let ix = ctx.sweep_excess(&grant, &grant.owner_tokens, SweepRecipient::Owner);
+    ctx.send(&[ix], &[&grant.grantor]).await.unwrap();
+    assert_eq!(ctx.balance(grant.owner_tokens).await, 200_000);
+    assert_eq!(ctx.balance(grant.contract.vault).await, 1_000_000);
+
+    let status = ctx.status(grant.contract.key).await;
+    assert_eq!(status.claimed_amount, 200_000);
+    assert_eq!(status.locked_amount, 1_000_000);
+}
+
+#[tokio::test]
+async fn test_claim_to_associated() {
+    let mut ctx = TestContext::new().await;
+    let now = ctx.now().await;
+    let schedule = [VestingSchedule {
+        unlock_timestamp: now + SECONDS_IN_HOUR,
+        quantity: 1_000_000,
+    }];
+    let grant = ctx.grant(&schedule).await;
+    ctx.create(&grant).await.unwrap();
+    ctx.warp_to(schedule[0].unlock_timestamp).await;
+
+    let owner_ata = spl_associated_token_account::get_associated_token_address(
+        &grant.owner.pubkey(),
+        &ctx.mint,
+    );
+
+    // Only wrapped SOL can be unwrapped
+    let ix = ctx.claim_to_associated(&grant, ClaimFlag::UnwrapNative as u64);
+    assert!(ctx.send(&[ix], &[&grant.owner]).await.is_err());
+
+    // The associated token account of the owner is created by the claim
+    let ix = ctx.claim_to_associated(&grant, 0);
+    ctx.send(&[ix], &[&grant.owner]).await.unwrap();
+    assert_eq!(ctx.balance(owner_ata).await, 1_000_000);
+}
+
+#[tokio::test]
+async fn test_claim_to_associated_native() {
+    let mut ctx = TestContext::new_native().await;
+    let now = ctx.now().await;
+    let schedule = [VestingSchedule {
+        unlock_timestamp: now + SECONDS_IN_HOUR,
+        quantity: 1_000_000,
+    }];
+    let grant = ctx.grant(&schedule).await;
+    ctx.create(&grant).await.unwrap();
+    ctx.warp_to(schedule[0].unlock_timestamp).await;
+
+    let owner = grant.owner.pubkey();
+    let owner_ata = grant.owner_tokens;
+    let unwrap_ix = ctx.claim_to_associated(&grant, ClaimFlag::UnwrapNative as u64);
+
+    // Unwrapping would also close over the tokens the owner already holds
+    ctx.mint_to(&owner_ata, 5).await;
+    assert!(ctx
+        .send(std::slice::from_ref(&unwrap_ix), &[&grant.owner])
+        .await
+        .is_err());
+
+    let ix = spl_token::instruction::close_account(&spl_token::ID, &owner_ata, &owner, &owner, &[])
+        .unwrap();
+    ctx.send(&[ix], &[&grant.owner]).await.unwrap();
+
+    // The claimed tokens are unwrapped along with the rent of the recreated account
+    let lamports = ctx.lamports(owner).await;
+    ctx.send(&[unwrap_ix], &[&grant.owner]).await.unwrap();
+    let ata_rent = Rent::default().minimum_balance(spl_token::state::Account::LEN);
+    assert_eq!(ctx.lamports(owner).await, lamports + 1_000_000 + ata_rent);
+    assert_eq!(ctx.balance(grant.contract.vault).await, 0);
+}
+
+#[tokio::test]
+async fn test_reclaim() {
+    let mut ctx = TestContext::new().await;
+    let now = ctx.now().await;
+    let schedule = (1..=2)
+        .map(|i| VestingSchedule {
+            unlock_timestamp: now + i * SECONDS_IN_HOUR,
+            quantity: 1_000_000,
+        })
+        .collect::<Vec<_>>();
+    let expiry_timestamp = now + 3 * SECONDS_IN_HOUR;
+    let mut grant = ctx.grant(&schedule).await;
+
+    // The expiry must leave time to claim every unlock
+    grant.create.expiry_timestamp = schedule[1].unlock_timestamp;
+    assert!(ctx.create(&grant).await.is_err());
+
+    grant.create.expiry_timestamp = expiry_timestamp;
+    ctx.create(&grant).await.unwrap();
+
+    // The first contract of the mint creates its statistics account
+    let stats = ctx.mint_stats().await;
+    assert_eq!(stats.mint, ctx.mint);
+    assert_eq!(stats.total_locked, 2_000_000);
+    assert_eq!(stats.total_claimed, 0);
+    assert_eq!(stats.active_contracts, 1);
+    assert_eq!(stats.total_contracts, 1);
+
+    // The owner claims the first unlock before the expiry
+    ctx.warp_to(schedule[0].unlock_timestamp).await;
+    ctx.send(&[ctx.claim(&grant)], &[&grant.owner])
+        .await
+        .unwrap();
+
+    let stats = ctx.mint_stats().await;
+    assert_eq!(stats.total_locked, 1_000_000);
+    assert_eq!(stats.total_claimed, 1_000_000);
+    assert_eq!(stats.active_contracts, 1);
+
+    // The grantor cannot reclaim the vault before the expiry
+    assert!(ctx
+        .send(&[ctx.reclaim(&grant)], &[&grant.grantor])
+        .await
+        .is_err());
+
+    // Once the contract expires, the owner cannot claim anymore and the grantor gets the rest back
+    ctx.warp_to(expiry_timestamp).await;
+    let status = ctx.status(grant.contract.key).await;
+    assert_eq!(status.claimable_amount, 0);
+    assert_eq!(status.locked_amount, 0);
+    assert!(ctx
+        .send(&[ctx.claim(&grant)], &[&grant.owner])
+        .await
+        .is_err());
+    ctx.send(&[ctx.reclaim(&grant)], &[&grant.grantor])
+        .await
+        .unwrap();
+
+    assert_eq!(ctx.balance(grant.owner_tokens).await, 1_000_000);
+    assert_eq!(ctx.balance(grant.grantor_tokens).await, 1_000_000);
+
+    // The reclaimed tokens are no longer locked and the contract is no longer active
+    let stats = ctx.mint_stats().await;
+    assert_eq!(stats.total_locked, 0);
+    assert_eq!(stats.total_claimed, 1_000_000);
+    assert_eq!(stats.active_contracts, 0);
+    assert_eq!(stats.total_contracts, 1);
+
+    let mut data = ctx.contract_data(grant.contract.key).await;
+    let vesting_contract = VestingContract::from_buffer(&mut data, Tag::VestingContract).unwrap();
+    assert_eq!(vesting_contract.extension.as_ref().unwrap().is_expired, 1);
+}
+
+#[tokio::test]
+async fn test_split_destinations() {
+    let mut ctx = TestContext::new().await;
+    let now = ctx.now().await;
+    let schedule = [VestingSchedule {
+        unlock_timestamp: now + SECONDS_IN_HOUR,
+        quantity: 1_000_000,
+    }];
+    let grant = ctx.grant(&schedule).await;
+    ctx.create(&grant).await.unwrap();
+
+    let savings = ctx.token_accounts(&[Pubkey::new_unique()]).await[0];
+    let set_split_ix = |destinations| {
+        token_vesting::instruction::set_split_destinations(
+            token_vesting::instruction::set_split_destinations::Accounts {
+                vesting_contract: &grant.contract.key,
+                owner: &grant.owner.pubkey(),
+                co_signers: &[],
+            },
+            token_vesting::instruction::set_split_destinations::Params { destinations },
+        )
+    };
+    let split = |token_account, bps| SplitDestination { token_account, bps };
+
+    // The shares should add up to a whole release
+    let mut destinations = [SplitDestination::zeroed(); 4];
+    destinations[0] = split(grant.owner_tokens, 7_000);
+    destinations[1] = split(savings, 2_000);
+    assert!(ctx
+        .send(&[set_split_ix(destinations)], &[&grant.owner])
+        .await
+        .is_err());
+
+    destinations[1] = split(savings, 3_000);
+    ctx.send(&[set_split_ix(destinations)], &[&grant.owner])
+        .await
+        .unwrap();
+    ctx.warp_to(schedule[0].unlock_timestamp).await;
+
+    // Every registered destination should be provided
+    assert!(ctx
+        .send(&[ctx.claim(&grant)], &[&grant.owner])
+        .await
+        .is_err());
+
+    let mut claim_ix = ctx.claim(&grant);
+    claim_ix.accounts.push(AccountMeta::new(savings, false));
+    ctx.send(&[claim_ix], &[&grant.owner]).await.unwrap();
+
+    assert_eq!(ctx.balance(grant.owner_tokens).await, 700_000);
+    assert_eq!(ctx.balance(savings).await, 300_000);
+}
+
+#[tokio::test]
+async fn test_withholding() {
+    let tax_authority = Keypair::new();
+
+    let mut ctx = TestContext::new().await;
+    let now = ctx.now().await;
+    let schedule = [VestingSchedule {
+        unlock_timestamp: now + SECONDS_IN_HOUR,
+        quantity: 1_000_001,
+    }];
+    let withholding_tokens = ctx.token_accounts(&[tax_authority.pubkey()]).await[0];
+
+    // The grantor withholds a quarter of each release
+    let mut grant = ctx.grant(&schedule).await;
+    grant.create.withholding_token_account = Some(withholding_tokens);
+    grant.create.withholding_bps = 2_500;
+    ctx.create(&grant).await.unwrap();
+
+    ctx.warp_to(schedule[0].unlock_timestamp).await;
+
+    let claim_ix = |withholding_token_account: Option<Pubkey>| {
+        let mut ix = ctx.claim(&grant);
+        ix.accounts
+            .extend(withholding_token_account.map(|key| AccountMeta::new(key, false)));
+        ix
+    };
+    let ix_without_withholding = claim_ix(None);
+    let ix_to_grantor = claim_ix(Some(grant.grantor_tokens));
+    let ix = claim_ix(Some(withholding_tokens));
+
+    // The owner cannot claim without routing the withheld share
+    assert!(ctx
+        .send(&[ix_without_withholding], &[&grant.owner])
+        .await
+        .is_err());
+    assert!(ctx.send(&[ix_to_grantor], &[&grant.owner]).await.is_err());
+
+    ctx.send(&[ix], &[&grant.owner]).await.unwrap();
+    assert_eq!(ctx.balance(withholding_tokens).await, 250_000);
+    assert_eq!(ctx.balance(grant.owner_tokens).await, 750_001);
+
+    // The excess cannot be granted to the owner as a bonus, which would skip the withholding
+    ctx.mint_to(&grant.contract.vault, 200_000).await;
+    let ix = ctx.sweep_excess(&grant, &grant.owner_tokens, SweepRecipient::Owner);
+    assert!(ctx.send(&[ix], &[&grant.grantor]).await.is_err());
+    let ix = ctx.sweep_excess(&grant, &grant.grantor_tokens, SweepRecipient::Grantor);
+    ctx.send(&[ix], &[&grant.grantor]).await.unwrap();
+    assert_eq!(ctx.balance(grant.owner_tokens).await, 750_001);
+}
+
+#[tokio::test]
+async fn test_approve_milestone() {
+    let approver = Keypair::new();
+
+    let mut ctx = TestContext::new().await;
+    let now = ctx.now().await;
+    let schedule = [VestingSchedule {
+        unlock_timestamp: now + SECONDS_IN_HOUR,
+        quantity: 1_000_000,
+    }];
+
+    // The release of the only entry waits for the approval of a milestone
+    let mut grant = ctx.grant(&schedule).await;
+    grant.create.milestone_approver = approver.pubkey();
+    grant.create.milestone_gated.insert(0);
+    ctx.create(&grant).await.unwrap();
+
+    ctx.warp_to(schedule[0].unlock_timestamp).await;
+
+    let approve_ix = |approver: &Pubkey| {
+        token_vesting::instruction::approve_milestone(
+            token_vesting::instruction::approve_milestone::Accounts {
+                vesting_contract: &grant.contract.key,
+                approver,
+            },
+            token_vesting::instruction::approve_milestone::Params { schedule_index: 0 },
+        )
+    };
+
+    // The entry is unlocked but its milestone isn't approved yet
+    assert!(ctx
+        .send(&[ctx.claim(&grant)], &[&grant.owner])
+        .await
+        .is_err());
+
+    // Only the approver can approve the milestone
+    assert!(ctx
+        .send(&[approve_ix(&grant.owner.pubkey())], &[&grant.owner])
+        .await
+        .is_err());
+    ctx.send(&[approve_ix(&approver.pubkey())], &[&approver])
+        .await
+        .unwrap();
+
+    ctx.send(&[ctx.claim(&grant)], &[&grant.owner])
+        .await
+        .unwrap();
+    assert_eq!(ctx.balance(grant.owner_tokens).await, 1_000_000);
+}
+
+#[tokio::test]
+async fn test_owner_signer_set() {
+    let co_signers = [Keypair::new(), Keypair::new(), Keypair::new()];
+
+    let mut ctx = TestContext::new().await;
+    let now = ctx.now().await;
+    let schedule = [VestingSchedule {
+        unlock_timestamp: now + SECONDS_IN_HOUR,
+        quantity: 1_000_000,
+    }];
+
+    // The owner shares their role with two of three keys
+    let mut grant = ctx.grant(&schedule).await;
+    grant.create.owner_signers.threshold = 2;
+    for (key, co_signer) in grant.create.owner_signers.keys.iter_mut().zip(&co_signers) {
+        *key = co_signer.pubkey();
+    }
+    ctx.create(&grant).await.unwrap();
+
+    ctx.warp_to(schedule[0].unlock_timestamp).await;
+
+    let co_signer_keys = co_signers.iter().map(|k| k.pubkey()).collect::<Vec<_>>();
+    let claim_ix = |co_signers: &[Pubkey]| {
+        token_vesting::instruction::claim(
+            token_vesting::instruction::claim::Accounts {
+                spl_token_program: &spl_token::ID,
+                vesting_contract: &grant.contract.key,
+                vesting_contract_signer: &grant.contract.vault_signer,
+                vault: &grant.contract.vault,
+                destination_token_account: &grant.owner_tokens,
+                owner: &grant.owner.pubkey(),
+                mint_stats: Some(&ctx.mint_stats),
+                extra_destination_token_accounts: &[],
+                co_signers,
+            },
+            token_vesting::instruction::claim::Params {},
+        )
+    };
+    let ix_without_co_signers = claim_ix(&[]);
+    let ix_below_threshold = claim_ix(&co_signer_keys[..1]);
+    let ix = claim_ix(&co_signer_keys[1..]);
+
+    // The signature of the owner alone doesn't hold the owner role anymore
+    assert!(ctx
+        .send(&[ix_without_co_signers], &[&grant.owner])
+        .await
+        .is_err());
+    assert!(ctx
+        .send(&[ix_below_threshold], &[&grant.owner, &co_signers[0]])
+        .await
+        .is_err());
+
+    ctx.send(&[ix], &[&grant.owner, &co_signers[1], &co_signers[2]])
+        .await
+        .unwrap();
+    assert_eq!(ctx.balance(grant.owner_tokens).await, 1_000_000);
+}
+
+#[tokio::test]
+async fn test_owner_signer_set_cannot_unwrap() {
+    let co_signer = Keypair::new();
+
+    let mut ctx = TestContext::new_native().await;
+    let now = ctx.now().await;
+    let schedule = [VestingSchedule {
+        unlock_timestamp: now + SECONDS_IN_HOUR,
+        quantity: 1_000_000,
+    }];
+    let mut grant = ctx.grant(&schedule).await;
+    grant.create.owner_signers.threshold = 1;
+    grant.create.owner_signers.keys[0] = co_signer.pubkey();
+    ctx.create(&grant).await.unwrap();
+    ctx.warp_to(schedule[0].unlock_timestamp).await;
+
+    // Closing the wrapped SOL account is left to the owner key, which no longer holds the role
+    let mut ix = ctx.claim_to_associated(&grant, ClaimFlag::UnwrapNative as u64);
+    ix.accounts
+        .push(AccountMeta::new_readonly(co_signer.pubkey(), true));
+    assert!(ctx.send(&[ix], &[&grant.owner, &co_signer]).await.is_err());
+
+    // The tokens can still be claimed as wrapped SOL
+    let mut ix = ctx.claim_to_associated(&grant, 0);
+    ix.accounts
+        .push(AccountMeta::new_readonly(co_signer.pubkey(), true));
+    ctx.send(&[ix], &[&grant.owner, &co_signer]).await.unwrap();
+    assert_eq!(ctx.balance(grant.owner_tokens).await, 1_000_000);
+}
+
+#[tokio::test]
+async fn test_freeze() {
+    let freeze_authority = Keypair::new();
+
+    let mut ctx = TestContext::new().await;
+    let now = ctx.now().await;
+    let schedule = [VestingSchedule {
+        unlock_timestamp: now + SECONDS_IN_HOUR,
+        quantity: 1_000_000,
+    }];
+    let mut grant = ctx.grant(&schedule).await;
+    grant.create.freeze_authority = freeze_authority.pubkey();
+    ctx.create(&grant).await.unwrap();
+
+    let freeze_ix = token_vesting::instruction::freeze(
+        token_vesting::instruction::freeze::Accounts {
+            vesting_contract: &grant.contract.key,
+            freeze_authority: &freeze_authority.pubkey(),
+        },
+        token_vesting::instruction::freeze::Params {},
+    );
+    let thaw_ix = || {
+        token_vesting::instruction::thaw(
+            token_vesting::instruction::thaw::Accounts {
+                vesting_contract: &grant.contract.key,
+                freeze_authority: &freeze_authority.pubkey(),
+            },
+            token_vesting::instruction::thaw::Params {
+                flags: ThawFlag::ExtendUnlocks as u64,
+            },
+        )
+    };
+
+    // The contract is frozen until the first unlock
+    ctx.send(&[freeze_ix], &[&freeze_authority]).await.unwrap();
+    ctx.warp_to(schedule[0].unlock_timestamp).await;
+    assert!(ctx
+        .send(&[ctx.claim(&grant)], &[&grant.owner])
+        .await
+        .is_err());
+
+    // The excess cannot be granted to the owner as a bonus while the contract is frozen
+    ctx.mint_to(&grant.contract.vault, 200_000).await;
+    let ix = ctx.sweep_excess(&grant, &grant.owner_tokens, SweepRecipient::Owner);
+    assert!(ctx.send(&[ix], &[&grant.grantor]).await.is_err());
+
+    // Thawing pushes the unlock back by the frozen duration
+    ctx.send(&[thaw_ix()], &[&freeze_authority]).await.unwrap();
+    assert!(ctx.send(&[thaw_ix()], &[&freeze_authority]).await.is_err());
+
+    let mut data = ctx.contract_data(grant.contract.key).await;
+    let vesting_contract = VestingContract::from_buffer(&mut data, Tag::VestingContract).unwrap();
+    let extension = vesting_contract.extension.as_ref().unwrap();
+    let frozen_duration = extension.thaw_timestamp - extension.freeze_timestamp;
+    assert!(frozen_duration >= SECONDS_IN_HOUR);
+    let unlock_timestamp = vesting_contract.schedules[0].unlock_timestamp;
+    assert_eq!(
+        unlock_timestamp,
+        schedule[0].unlock_timestamp + frozen_duration
+    );
+
+    ctx.warp_to(unlock_timestamp).await;
+    ctx.send(&[ctx.claim(&grant)], &[&grant.owner])
+        .await
+        .unwrap();
+    assert_eq!(ctx.balance(grant.owner_tokens).await, 1_000_000);
+}
+
+#[tokio::test]
+async fn test_reclaim_frozen() {
+    let freeze_authority = Keypair::new();
+
+    let mut ctx = TestContext::new().await;
+    let now = ctx.now().await;
+    let expiry_timestamp = now + 2 * SECONDS_IN_HOUR;
+    let mut grant = ctx
+        .grant(&[VestingSchedule {
+            unlock_timestamp: now + SECONDS_IN_HOUR,
+            quantity: 1_000_000,
+        }])
+        .await;
+    grant.create.freeze_authority = freeze_authority.pubkey();
+    grant.create.expiry_timestamp = expiry_timestamp;
+    ctx.create(&grant).await.unwrap();
+
+    let freeze_ix = token_vesting::instruction::freeze(
+        token_vesting::instruction::freeze::Accounts {
+            vesting_contract: &grant.contract.key,
+            freeze_authority: &freeze_authority.pubkey(),
+        },
+        token_vesting::instruction::freeze::Params {},
+    );
+    let thaw_ix = token_vesting::instruction::thaw(
+        token_vesting::instruction::thaw::Accounts {
+            vesting_contract: &grant.contract.key,
+            freeze_authority: &freeze_authority.pubkey(),
+        },
+        token_vesting::instruction::thaw::Params { flags: 0 },
+    );
+
+    // The grantor cannot reclaim the vault of an expired contract while it is frozen
+    ctx.send(&[freeze_ix], &[&freeze_authority]).await.unwrap();
+    ctx.warp_to(expiry_timestamp).await;
+    assert!(ctx
+        .send(&[ctx.reclaim(&grant)], &[&grant.grantor])
+        .await
+        .is_err());
+
+    // The expiry isn't pushed back by the thaw, so the vault can be reclaimed right away
+    ctx.send(&[thaw_ix], &[&freeze_authority]).await.unwrap();
+    ctx.send(&[ctx.reclaim(&grant)], &[&grant.grantor])
+        .await
+        .unwrap();
+    assert_eq!(ctx.balance(grant.grantor_tokens).await, 1_000_000);
+}
+
+#[tokio::test]
+async fn test_owner_registry() {
+    let mut ctx = TestContext::new().await;
+    let now = ctx.now().await;
+    let schedule = [VestingSchedule {
+        unlock_timestamp: now + SECONDS_IN_HOUR,
+        quantity: 1_000_000,
+    }];
+
+    // The same parties make two grants, each of which is appended to the registry of the owner
+    let mut grant = ctx.grant(&schedule).await;
+    let (owner_registry, _) =
+        OwnerRegistry::find_address(&grant.owner.pubkey(), &token_vesting::ID);
+    let mut vesting_contracts = vec![];
+    for _ in 0..2 {
+        ctx.mint_to(&grant.grantor_tokens, 1_000_000).await;
+        grant.contract = ctx.new_contract(schedule.len()).await;
+        grant.create.vesting_contract = grant.contract.key;
+        grant.create.vault = grant.contract.vault;
+        grant.create.signer_nonce = grant.contract.vault_signer_nonce as u64;
+        grant.create.owner_registry = Some(owner_registry);
+        grant.create.flags = CreateFlag::RegisterOwner as u64;
+        ctx.create(&grant).await.unwrap();
+        vesting_contracts.push(grant.contract.key);
+    }
+
+    // A single account lists every contract of the owner
+    let mut account = ctx
+        .prg_test_ctx
+        .banks_client
+        .get_account(owner_registry)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(account.owner, token_vesting::ID);
+    assert_eq!(
+        account.data.len(),
+        OwnerRegistry::compute_allocation_size(2)
+    );
+    assert!(Rent::default().is_exempt(account.lamports, account.data.len()));
+    let registry = OwnerRegistry::from_buffer(&mut account.data).unwrap();
+    assert_eq!(registry.header.owner, grant.owner.pubkey());
+    assert_eq!(registry.contracts, &vesting_contracts[..]);
+
+    let mut data = ctx.contract_data(vesting_contracts[0]).await;
+    let vesting_contract = VestingContract::from_buffer(&mut data, Tag::VestingContract).unwrap();
+    assert_eq!(
+        vesting_contract.extension.as_ref().unwrap().is_registered,
+        1
+    );
+}
+
+#[tokio::test]
+async fn test_cumulative_layout() {
+    let mut ctx = TestContext::new().await;
+    let now = ctx.now().await;
+    let schedule = (1..=3)
+        .map(|i| VestingSchedule {
+            unlock_timestamp: now + i * SECONDS_IN_HOUR,
+            quantity: i * 1_000_000,
+        })
+        .collect::<Vec<_>>();
+    let mut grant = ctx.grant(&schedule).await;
+    grant.create.flags = CreateFlag::CumulativeLayout as u64;
+
+    // Entries gated by a milestone can't be stored as cumulative quantities
+    grant.create.milestone_approver = grant.grantor.pubkey();
+    grant.create.milestone_gated.insert(0);
+    assert!(ctx.create(&grant).await.is_err());
+
+    grant.create.milestone_approver = Pubkey::default();
+    grant.create.milestone_gated = MilestoneBitmap::zeroed();
+    ctx.create(&grant).await.unwrap();
+
+    let mut data = ctx.contract_data(grant.contract.key).await;
+    let vesting_contract = VestingContract::from_buffer(&mut data, Tag::VestingContract).unwrap();
+    assert!(vesting_contract.is_cumulative());
+    assert_eq!(
+        vesting_contract
+            .schedules
+            .iter()
+            .map(|s| s.quantity)
+            .collect::<Vec<_>>(),
+        vec![1_000_000, 3_000_000, 6_000_000]
+    );
+
+    // The owner claims the first two unlocks at once
+    ctx.warp_to(schedule[1].unlock_timestamp).await;
+    ctx.send(&[ctx.claim(&grant)], &[&grant.owner])
+        .await
+        .unwrap();
+    assert_eq!(ctx.balance(grant.owner_tokens).await, 3_000_000);
+
+    let stats = ctx.mint_stats().await;
+    assert_eq!(stats.total_locked, 3_000_000);
+    assert_eq!(stats.total_claimed, 3_000_000);
+}
@@ -0,0 +1,154 @@
+use solana_program::pubkey::Pubkey;
+use token_vesting::{
+    error::TokenVestingError,
+    state::{
+        owner_registry::OwnerRegistry,
+        vesting_contract::{
+            ContractMetadata, LegacyVestingContractHeader, VestingContract,
+            VestingContractExtension, VestingContractHeader,
+        },
+        Tag,
+    },
+};
+
+#[test]
+fn test_memcmp_offsets() {
+    // The header keeps the size of the deployed accounts, new fields go in the extension
+    assert_eq!(VestingContractHeader::LEN, 80);
+    assert_eq!(LegacyVestingContractHeader::LEN, VestingContractHeader::LEN);
+    // The extension must keep its size as new fields are added
+    assert_eq!(VestingContractExtension::LEN, 1080);
+
+    let mut buffer = vec![0; VestingContract::compute_allocation_size(1)];
+    VestingContract::initialize(&mut buffer).unwrap();
+
+    let owner = Pubkey::new_unique();
+    let vault = Pubkey::new_unique();
+    let grantor = Pubkey::new_unique();
+    let mint = Pubkey::new_unique();
+    {
+        let mut contract = VestingContract::from_buffer(&mut buffer, Tag::VestingContract).unwrap();
+        contract.header.owner = owner;
+        contract.header.vault = vault;
+        let extension = contract.extension_mut().unwrap();
+        extension.grantor = grantor;
+        extension.mint = mint;
+    }
+
+    let field = |offset: usize| &buffer[offset..offset + 32];
+    assert_eq!(field(VestingContract::OWNER_OFFSET), owner.as_ref());
+    assert_eq!(field(VestingContract::VAULT_OFFSET), vault.as_ref());
+    assert_eq!(field(VestingContract::GRANTOR_OFFSET), grantor.as_ref());
+    assert_eq!(field(VestingContract::MINT_OFFSET), mint.as_ref());
+    assert_eq!(
+        buffer[VestingContract::VERSION_OFFSET],
+        VestingContractHeader::CURRENT_VERSION
+    );
+}
+
+#[test]
+fn test_legacy_layout() {
+    let owner = Pubkey::new_unique();
+    let vault = Pubkey::new_unique();
+
+    // The bytes are laid out the way the legacy program wrote them
+    let mut buffer = (Tag::VestingContract as u64).to_le_bytes().to_vec();
+    buffer.extend_from_slice(&owner.to_bytes());
+    buffer.extend_from_slice(&vault.to_bytes());
+    buffer.extend_from_slice(&1u64.to_le_bytes());
+    buffer.push(254);
+    buffer.extend_from_slice(&[0; 7]);
+    for (unlock_timestamp, quantity) in [(10u64, 0u64), (20, 500)] {
+        buffer.extend_from_slice(&unlock_timestamp.to_le_bytes());
+        buffer.extend_from_slice(&quantity.to_le_bytes());
+    }
+    assert_eq!(
+        buffer.len(),
+        VestingContract::compute_legacy_allocation_size(2)
+    );
+
+    {
+        let contract = VestingContract::from_buffer(&mut buffer, Tag::VestingContract).unwrap();
+        assert!(contract.extension.is_none());
+        assert_eq!(contract.header.owner, owner);
+        assert_eq!(contract.header.vault, vault);
+        assert_eq!(contract.header.signer_nonce, 254);
+        assert_eq!(contract.schedules.len(), 2);
+        assert_eq!(contract.schedules[1].quantity, 500);
+        assert_eq!(contract.grantor(), Err(TokenVestingError::LegacyLayout));
+    }
+
+    // The migrated header keeps the fields of the legacy one
+    let legacy_header: LegacyVestingContractHeader =
+        *bytemuck::from_bytes(&buffer[8..8 + LegacyVestingContractHeader::LEN]);
+    assert!(legacy_header.is_legacy());
+    let header = legacy_header.upgrade();
+    assert_eq!(header.owner, owner);
+    assert_eq!(header.current_schedule_index, 1);
+    assert_eq!(header.version, VestingContractHeader::CURRENT_VERSION);
+
+    // Headers with an unknown version aren't mistaken for legacy ones
+    buffer[VestingContract::VERSION_OFFSET] = 2;
+    assert_eq!(
+        VestingContract::from_buffer(&mut buffer, Tag::VestingContract).err(),
+        Some(TokenVestingError::DataTypeMismatch)
+    );
+}
+
+#[test]
+fn test_contract_metadata() {
+    let metadata = ContractMetadata::new("Advisor grant", "ipfs://grant").unwrap();
+    assert_eq!(metadata.label().unwrap(), "Advisor grant");
+    assert_eq!(metadata.uri().unwrap(), "ipfs://grant");
+
+    // Strings which don't fit are rejected
+    assert_eq!(
+        ContractMetadata::new(&"a".repeat(33), "").err(),
+        Some(TokenVestingError::InvalidMetadata)
+    );
+
+    // Invalid UTF-8 and bytes after the padding are rejected
+    let mut invalid = metadata;
+    invalid.label[0] = 0xff;
+    assert_eq!(invalid.check(), Err(TokenVestingError::InvalidMetadata));
+    let mut invalid = metadata;
+    invalid.uri[127] = b'a';
+    assert_eq!(invalid.check(), Err(TokenVestingError::InvalidMetadata));
+
+    // Empty values are reported as missing
+    let mut buffer = vec![0; VestingContract::compute_allocation_size(1)];
+    VestingContract::initialize(&mut buffer).unwrap();
+    let mut contract = VestingContract::from_buffer(&mut buffer, Tag::VestingContract).unwrap();
+    assert_eq!(contract.label(), None);
+    contract.extension_mut().unwrap().metadata = metadata;
+    assert_eq!(contract.label(), Some("Advisor grant"));
+    assert_eq!(contract.uri(), Some("ipfs://grant"));
+}
+
+#[test]
+fn test_owner_registry() {
+    let owner = Pubkey::new_unique();
+    let contracts = [Pubkey::new_unique(), Pubkey::new_unique()];
+
+    // Registries start empty and grow by one key per contract
+    let mut buffer = vec![0; OwnerRegistry::compute_allocation_size(0)];
+    OwnerRegistry::initialize(&mut buffer, &owner).unwrap();
+    assert!(OwnerRegistry::from_buffer(&mut buffer)
+        .unwrap()
+        .contracts
+        .is_empty());
+
+    buffer.resize(OwnerRegistry::compute_allocation_size(2), 0);
+    let registry = OwnerRegistry::from_buffer(&mut buffer).unwrap();
+    registry.contracts.copy_from_slice(&contracts);
+    assert_eq!(&buffer[8..40], owner.as_ref());
+    assert_eq!(&buffer[40..72], contracts[0].as_ref());
+    assert_eq!(&buffer[72..104], contracts[1].as_ref());
+
+    // Truncated entries and other account types are rejected
+    buffer.push(0);
+    assert!(OwnerRegistry::from_buffer(&mut buffer).is_err());
+    let mut buffer = vec![0; VestingContract::compute_allocation_size(1)];
+    VestingContract::initialize(&mut buffer).unwrap();
+    assert!(OwnerRegistry::from_buffer(&mut buffer).is_err());
+}
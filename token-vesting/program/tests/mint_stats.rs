@@ -0,0 +1,72 @@
+use solana_program::pubkey::Pubkey;
+use token_vesting::{
+    error::TokenVestingError,
+    state::{mint_stats::MintStats, Tag},
+};
+
+#[test]
+fn test_initialize_and_read() {
+    let mint = Pubkey::new_unique();
+    let mut buffer = vec![0; MintStats::ALLOCATION_SIZE];
+    MintStats::initialize(&mut buffer, &mint).unwrap();
+    assert_eq!(buffer[0], Tag::MintStats as u8);
+
+    MintStats::from_buffer(&mut buffer)
+        .unwrap()
+        .record_create(1_000)
+        .unwrap();
+    let stats = MintStats::read(&buffer).unwrap();
+    assert_eq!(stats.mint, mint);
+    assert_eq!(stats.total_locked, 1_000);
+    assert_eq!(stats.active_contracts, 1);
+    assert_eq!(stats.total_contracts, 1);
+
+    // Accounts can only be initialized once
+    assert_eq!(
+        MintStats::initialize(&mut buffer, &mint),
+        Err(TokenVestingError::DataTypeMismatch)
+    );
+    // Other accounts of the program aren't mistaken for statistics
+    buffer[0] = Tag::VestingContract as u8;
+    assert_eq!(
+        MintStats::read(&buffer),
+        Err(TokenVestingError::DataTypeMismatch)
+    );
+    assert!(MintStats::from_buffer(&mut buffer[..MintStats::LEN]).is_err());
+}
+
+#[test]
+fn test_record() {
+    let mut buffer = vec![0; MintStats::ALLOCATION_SIZE];
+    MintStats::initialize(&mut buffer, &Pubkey::new_unique()).unwrap();
+    let stats = MintStats::from_buffer(&mut buffer).unwrap();
+
+    stats.record_create(1_000).unwrap();
+    stats.record_create(500).unwrap();
+    assert_eq!(stats.total_locked, 1_500);
+    assert_eq!(stats.active_contracts, 2);
+
+    // The first contract is partially claimed, then fully
+    stats.record_claim(400, false).unwrap();
+    stats.record_claim(600, true).unwrap();
+    assert_eq!(stats.total_locked, 500);
+    assert_eq!(stats.total_claimed, 1_000);
+    assert_eq!(stats.active_contracts, 1);
+
+    // A bonus is claimed without being locked first
+    stats.record_bonus(50).unwrap();
+    assert_eq!(stats.total_locked, 500);
+    assert_eq!(stats.total_claimed, 1_050);
+
+    // The second contract is reclaimed
+    stats.record_reclaim(500, true).unwrap();
+    assert_eq!(stats.total_locked, 0);
+    assert_eq!(stats.active_contracts, 0);
+    assert_eq!(stats.total_contracts, 2);
+
+    // Counters never wrap around
+    assert_eq!(
+        stats.record_claim(1, false),
+        Err(TokenVestingError::Overflow)
+    );
+}
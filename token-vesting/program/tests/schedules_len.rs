@@ -2,13 +2,21 @@
 use bonfida_test_utils::{ProgramTestContextExt, ProgramTestExt};
 use bonfida_utils::bench::get_env_arg;
 use borsh::BorshSerialize;
-use solana_program::pubkey::Pubkey;
+use bytemuck::Zeroable;
 use solana_program_test::{processor, ProgramTest};
-use solana_sdk::{account::Account, signature::Keypair, signer::Signer};
+use solana_sdk::{
+    account::Account, pubkey::Pubkey, signature::Keypair, signer::Signer, system_program,
+};
 use std::cell::RefCell;
 use token_vesting::{
     entrypoint::process_instruction,
-    state::vesting_contract::{VestingContract, VestingSchedule},
+    instruction::create::CreateFlag,
+    state::{
+        mint_stats::MintStats,
+        vesting_contract::{
+            ContractMetadata, MilestoneBitmap, SignerSet, VestingContract, VestingSchedule,
+        },
+    },
 };
 pub mod common;
 use crate::common::utils::sign_send_instructions;
@@ -20,6 +28,15 @@ async fn main() {
 
 async fn run() {
     let schedule_length = get_env_arg(0).unwrap_or(100);
+    // The index of the schedule the claim is made at, releasing every schedule up to it
+    let claim_position = get_env_arg(1).unwrap_or(0);
+    // Non-zero to create the contract with the cumulative layout
+    let is_cumulative = get_env_arg(2).unwrap_or(0) != 0;
+    let flags = if is_cumulative {
+        CreateFlag::CumulativeLayout as u64
+    } else {
+        0
+    };
     // Create program and test environment
     const ALICE: usize = 0;
     const BOB: usize = 1;
@@ -36,6 +53,7 @@ async fn run() {
     );
 
     let (mint_key, _) = program_test.add_mint(None, 6, &keypairs[MINT_AUTHORITY].pubkey());
+    let (mint_stats, _) = MintStats::find_address(&mint_key, &token_vesting::ID);
 
     ////
     // Create test context
@@ -87,7 +105,7 @@ async fn run() {
         .unwrap();
 
     let (vault_signer, vault_signer_nonce) =
-        Pubkey::find_program_address(&[&vesting_contract.to_bytes()], &token_vesting::ID);
+        VestingContract::find_signer_address(&vesting_contract, &token_vesting::ID);
     let vault = prg_test_ctx
         .initialize_token_accounts(mint_key, &[vault_signer])
         .await
@@ -102,9 +120,24 @@ async fn run() {
             source_tokens: &ata_keys[ALICE],
             source_tokens_owner: &keypairs[ALICE].pubkey(),
             recipient: &keypairs[BOB].pubkey(),
+            system_program: &system_program::ID,
+            payer: &prg_test_ctx.payer.pubkey(),
+            mint_stats: &mint_stats,
+            owner_registry: None,
+            withholding_token_account: None,
         },
         token_vesting::instruction::create::Params {
             signer_nonce: &(vault_signer_nonce as u64),
+            flags: &flags,
+            metadata: &ContractMetadata::zeroed(),
+            activator: &Pubkey::default(),
+            expiry_timestamp: &0,
+            withholding_bps: &0,
+            milestone_approver: &Pubkey::default(),
+            milestone_gated: &MilestoneBitmap::zeroed(),
+            owner_signers: &SignerSet::zeroed(),
+            grantor_signers: &SignerSet::zeroed(),
+            freeze_authority: &Pubkey::default(),
             schedule: &schedule,
         },
     );
@@ -120,9 +153,9 @@ async fn run() {
         .unwrap()
         .amount;
 
-    // Let's claim the schedules one by one
+    // Let's claim the schedules up to the claim position at once
 
-    for v in schedule.into_iter().take(1) {
+    for v in schedule.into_iter().skip(claim_position).take(1) {
         // We fast-forward to the unlock
         // let previous_balance = prg_test_ctx
         //     .get_token_account(ata_keys[BOB])
@@ -141,6 +174,9 @@ async fn run() {
                 vault: &vault,
                 destination_token_account: &ata_keys[BOB],
                 owner: &keypairs[BOB].pubkey(),
+                mint_stats: Some(&mint_stats),
+                extra_destination_token_accounts: &[],
+                co_signers: &[],
             },
             token_vesting::instruction::claim::Params {},
         );
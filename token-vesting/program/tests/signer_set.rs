@@ -0,0 +1,62 @@
+use solana_program::pubkey::Pubkey;
+use token_vesting::{error::TokenVestingError, state::vesting_contract::SignerSet};
+
+fn signer_set(threshold: u64, keys: &[Pubkey]) -> SignerSet {
+    let mut signer_set = SignerSet {
+        threshold,
+        keys: [Pubkey::default(); SignerSet::MAX_KEYS],
+    };
+    signer_set.keys[..keys.len()].copy_from_slice(keys);
+    signer_set
+}
+
+#[test]
+fn test_check_signer_set() {
+    let keys = (0..3).map(|_| Pubkey::new_unique()).collect::<Vec<_>>();
+
+    assert_eq!(signer_set(0, &[]).check(), Ok(()));
+    assert_eq!(signer_set(2, &keys).check(), Ok(()));
+    assert_eq!(signer_set(3, &keys).check(), Ok(()));
+
+    // The threshold can't be met
+    assert_eq!(
+        signer_set(4, &keys).check(),
+        Err(TokenVestingError::InvalidSignerSet)
+    );
+    // Listed keys of a disabled set would be misleading
+    assert_eq!(
+        signer_set(0, &keys).check(),
+        Err(TokenVestingError::InvalidSignerSet)
+    );
+    // A key can't be counted twice
+    assert_eq!(
+        signer_set(2, &[keys[0], keys[0], keys[1]]).check(),
+        Err(TokenVestingError::InvalidSignerSet)
+    );
+    // Unused entries come last
+    assert_eq!(
+        signer_set(1, &[keys[0], Pubkey::default(), keys[1]]).check(),
+        Err(TokenVestingError::InvalidSignerSet)
+    );
+}
+
+#[test]
+fn test_count_signatures() {
+    let keys = (0..3).map(|_| Pubkey::new_unique()).collect::<Vec<_>>();
+    let signer_set = signer_set(2, &keys);
+    let outsider = Pubkey::new_unique();
+
+    assert_eq!(signer_set.count_signatures([].iter()), 0);
+    assert_eq!(signer_set.count_signatures([outsider].iter()), 0);
+    assert_eq!(signer_set.count_signatures([keys[2], outsider].iter()), 1);
+
+    // Repeated signers only count once
+    assert_eq!(signer_set.count_signatures([keys[0], keys[0]].iter()), 1);
+    assert_eq!(
+        signer_set.count_signatures([keys[0], keys[1], keys[2]].iter()),
+        3
+    );
+
+    // The unused entries never match
+    assert_eq!(signer_set.count_signatures([Pubkey::default()].iter()), 0);
+}
@@ -0,0 +1,94 @@
+use bytemuck::Zeroable;
+use solana_program::pubkey::Pubkey;
+use token_vesting::{
+    error::TokenVestingError,
+    state::{
+        vesting_contract::{SplitDestination, VestingContract},
+        Tag,
+    },
+};
+
+fn destination(bps: u64) -> SplitDestination {
+    SplitDestination {
+        token_account: Pubkey::new_unique(),
+        bps,
+    }
+}
+
+#[test]
+fn test_check_split() {
+    let zero = SplitDestination::zeroed();
+
+    // No destination at all means releases aren't divided
+    assert_eq!(SplitDestination::check_split(&[zero; 4]), Ok(0));
+    assert_eq!(
+        SplitDestination::check_split(&[destination(7_000), destination(3_000), zero, zero]),
+        Ok(2)
+    );
+
+    // The shares should add up to a whole release
+    assert_eq!(
+        SplitDestination::check_split(&[destination(7_000), destination(2_000), zero, zero]),
+        Err(TokenVestingError::InvalidSplit)
+    );
+
+    // Unused entries come last
+    assert_eq!(
+        SplitDestination::check_split(&[destination(7_000), zero, destination(3_000), zero]),
+        Err(TokenVestingError::InvalidSplit)
+    );
+
+    // Destinations are distinct
+    let d = destination(5_000);
+    assert_eq!(
+        SplitDestination::check_split(&[d, d, zero, zero]),
+        Err(TokenVestingError::InvalidSplit)
+    );
+}
+
+#[test]
+fn test_split_amount() {
+    let destinations = [destination(3_334), destination(3_333), destination(3_333)];
+
+    // The first destination receives the rounding remainder
+    assert_eq!(
+        SplitDestination::split_amount(&destinations, 100),
+        vec![34, 33, 33]
+    );
+    assert_eq!(
+        SplitDestination::split_amount(&destinations, 1),
+        vec![1, 0, 0]
+    );
+    for amount in [0, 7, 1_000_001, u64::MAX] {
+        let amounts = SplitDestination::split_amount(&destinations, amount);
+        assert_eq!(
+            amounts.iter().map(|a| *a as u128).sum::<u128>(),
+            amount as u128
+        );
+    }
+}
+
+#[test]
+fn test_withhold() {
+    let mut buffer = vec![0u8; VestingContract::compute_allocation_size(1)];
+    VestingContract::initialize(&mut buffer).unwrap();
+    let contract = VestingContract::from_buffer(&mut buffer, Tag::VestingContract).unwrap();
+
+    // Nothing is withheld by default
+    assert_eq!(contract.withholding_account(), None);
+    assert_eq!(contract.withhold(1_000), (0, 1_000));
+
+    let withholding_account = Pubkey::new_unique();
+    let extension = contract.extension.unwrap();
+    extension.withholding_account = withholding_account;
+    extension.withholding_bps = 2_500;
+    let contract = VestingContract::from_buffer(&mut buffer, Tag::VestingContract).unwrap();
+
+    // The withheld share is rounded down in favor of the owner
+    assert_eq!(contract.withholding_account(), Some(&withholding_account));
+    assert_eq!(contract.withhold(1_003), (250, 753));
+    assert_eq!(
+        contract.withhold(u64::MAX),
+        (u64::MAX / 4, u64::MAX - u64::MAX / 4)
+    );
+}